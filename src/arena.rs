@@ -0,0 +1,134 @@
+// A doubly linked list whose nodes live contiguously in a `Vec` and link to
+// each other by index, trading cheap splicing of shared nodes for better
+// cache locality when the list is mostly read.
+#[derive(Debug)]
+pub struct ArenaDoublyLinkedList<T> {
+    nodes: Vec<ArenaNode<T>>,
+    head: Option<usize>,
+    tail: Option<usize>,
+}
+
+#[derive(Debug)]
+struct ArenaNode<T> {
+    prev: Option<usize>,
+    value: T,
+    next: Option<usize>,
+}
+
+impl<T> Default for ArenaDoublyLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Preallocates from the iterator's lower size hint, so collecting an
+// exactly-sized iterator allocates the nodes once.
+impl<T> FromIterator<T> for ArenaDoublyLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut list = Self::with_capacity(iter.size_hint().0);
+        for value in iter {
+            list.push_back(value);
+        }
+        list
+    }
+}
+
+impl<T> ArenaDoublyLinkedList<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        ArenaDoublyLinkedList {
+            nodes: Vec::with_capacity(capacity),
+            head: None,
+            tail: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.nodes.capacity()
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let index = self.nodes.len();
+        self.nodes.push(ArenaNode {
+            prev: self.tail,
+            value,
+            next: None,
+        });
+        match self.tail {
+            Some(tail) => self.nodes[tail].next = Some(index),
+            None => self.head = Some(index),
+        }
+        self.tail = Some(index);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut index = self.head;
+        std::iter::from_fn(move || {
+            let node = &self.nodes[index?];
+            index = node.next;
+            Some(&node.value)
+        })
+    }
+
+    pub fn rev_iter(&self) -> impl Iterator<Item = &T> {
+        let mut index = self.tail;
+        std::iter::from_fn(move || {
+            let node = &self.nodes[index?];
+            index = node.prev;
+            Some(&node.value)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_can_push_to_the_back() {
+        let mut list = ArenaDoublyLinkedList::new();
+
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().copied().collect::<Vec<i32>>(), vec![1, 2, 3]);
+        assert_eq!(
+            list.rev_iter().copied().collect::<Vec<i32>>(),
+            vec![3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn it_preallocates_when_collected_from_an_exact_size_iterator() {
+        let list: ArenaDoublyLinkedList<i32> = vec![1, 2, 3, 4].into_iter().collect();
+
+        assert_eq!(list.capacity(), 4);
+        assert_eq!(list.iter().copied().collect::<Vec<i32>>(), vec![1, 2, 3, 4]);
+        assert_eq!(
+            list.rev_iter().copied().collect::<Vec<i32>>(),
+            vec![4, 3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn it_starts_out_empty() {
+        let list = ArenaDoublyLinkedList::<i32>::with_capacity(4);
+
+        assert!(list.is_empty());
+        assert_eq!(list.iter().count(), 0);
+    }
+}
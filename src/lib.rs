@@ -1,4 +1,18 @@
-use std::{cell::RefCell, fmt::Formatter, rc::Rc};
+mod arena;
+mod lru;
+
+pub use arena::ArenaDoublyLinkedList;
+pub use lru::LruCache;
+
+use std::{
+    cell::RefCell,
+    cmp::{Ordering, Reverse},
+    collections::{BTreeSet, BinaryHeap, HashMap, HashSet},
+    fmt::{Debug, Display, Formatter},
+    hash::{Hash, Hasher},
+    ops::{Add, Range, Sub},
+    rc::Rc,
+};
 
 #[macro_export]
 macro_rules! doubly_linked_list {
@@ -13,15 +27,13 @@ macro_rules! doubly_linked_list {
                         Some(node) => Some(Rc::clone(node)),
                         None => None
                     }),
-                    value: Rc::new($element),
+                    value: RefCell::new(Rc::new($element)),
                     next: RefCell::new(None),
                 }
             );
-            if let Some(prev) = ptr {
-                *prev.next.borrow_mut() = Some(Rc::clone(&current));
-                if root.is_none() {
-                    root = Some(Rc::clone(&prev));
-                }
+            match ptr {
+                Some(prev) => *prev.next.borrow_mut() = Some(Rc::clone(&current)),
+                None => root = Some(Rc::clone(&current)),
             }
             ptr = Some(Rc::clone(&current));
         )*
@@ -38,7 +50,41 @@ pub struct DoublyLinkedList<'a, T> {
     root: RefCell<Option<Rc<Node<'a, T>>>>,
 }
 
+impl<'a, T> Default for DoublyLinkedList<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Nodes point at each other in both directions, so the chain has to be taken
+// apart by hand for the nodes to be freed.
+impl<'a, T> Drop for DoublyLinkedList<'a, T> {
+    fn drop(&mut self) {
+        let mut node = self.root.take();
+        while let Some(current) = node {
+            node = current.next.take();
+            *current.prev.borrow_mut() = None;
+        }
+    }
+}
+
+// Lists only hold a root pointer, so there is nothing to preallocate from the
+// iterator's size hint; the nodes are linked in a single pass.
+impl<'a, T> FromIterator<T> for DoublyLinkedList<'a, T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let list = DoublyLinkedList::new();
+        list.push_back_many(iter);
+        list
+    }
+}
+
 impl<'a, T> DoublyLinkedList<'a, T> {
+    pub fn new() -> Self {
+        DoublyLinkedList {
+            root: RefCell::new(None),
+        }
+    }
+
     pub fn iter(&self) -> NodeIterator<'a, T> {
         let root = self.root.take();
         match root {
@@ -73,215 +119,3770 @@ impl<'a, T> DoublyLinkedList<'a, T> {
             },
         }
     }
-}
 
-struct Node<'a, T> {
-    prev: RefCell<Option<Rc<Node<'a, T>>>>,
-    value: Rc<T>,
-    next: RefCell<Option<Rc<Node<'a, T>>>>,
-}
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
 
-impl<'a, T> std::fmt::Debug for Node<'a, T>
-where
-    T: std::fmt::Debug,
-{
-    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
-        fmt.write_str(format!("{:?}", self.value).as_ref())?;
-        match self.next.take() {
-            Some(next) => {
-                fmt.write_str(",\n    ")?;
-                next.fmt(fmt)?;
-                *self.next.borrow_mut() = Some(next);
-            }
-            None => {}
+    pub fn is_empty(&self) -> bool {
+        self.root.borrow().is_none()
+    }
+
+    // Every sliding window of `size` consecutive values, like `slice::windows`.
+    pub fn windows(&self, size: usize) -> Vec<Vec<Rc<T>>> {
+        assert!(size > 0, "window size must be non-zero");
+        let values: Vec<Rc<T>> = self.iter().collect();
+        values.windows(size).map(|window| window.to_vec()).collect()
+    }
+
+    pub fn window_sums(&self, size: usize) -> Vec<T>
+    where
+        T: Add<Output = T> + Clone,
+    {
+        self.windows(size)
+            .iter()
+            .map(|window| {
+                window[1..]
+                    .iter()
+                    .fold((*window[0]).clone(), |sum, value| sum + (**value).clone())
+            })
+            .collect()
+    }
+
+    // Index of the first contiguous run of values equal to `pattern`.
+    pub fn find_subsequence(&self, pattern: &[T]) -> Option<usize>
+    where
+        T: PartialEq,
+    {
+        if pattern.is_empty() {
+            return Some(0);
         }
-        Ok(())
+        let values: Vec<Rc<T>> = self.iter().collect();
+        values.windows(pattern.len()).position(|window| {
+            window
+                .iter()
+                .zip(pattern)
+                .all(|(value, expected)| **value == *expected)
+        })
     }
-}
 
-impl<'a, T> std::fmt::Debug for DoublyLinkedList<'a, T>
-where
-    T: std::fmt::Debug,
-{
-    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
-        fmt.write_str("DoublyLinkedList {\n")?;
-        let root = self.root.take();
-        if let Some(root) = root {
-            fmt.write_str("    ")?;
-            *self.root.borrow_mut() = Some(Rc::clone(&root));
-            root.fmt(fmt)?;
-            fmt.write_str("\n")?;
+    // Replace the first run of values equal to `pattern` with `replacement`.
+    // Returns whether a match was found.
+    pub fn replace_subsequence(&self, pattern: &[T], replacement: &[T]) -> bool
+    where
+        T: PartialEq + Clone,
+    {
+        let start = match self.find_subsequence(pattern) {
+            Some(start) => start,
+            None => return false,
+        };
+        let mut nodes = self.nodes();
+        let replaced: Vec<Rc<Node<'a, T>>> = nodes
+            .splice(
+                start..start + pattern.len(),
+                replacement
+                    .iter()
+                    .map(|value| Node::new(Rc::new(value.clone()))),
+            )
+            .collect();
+        for node in replaced {
+            node.detach();
         }
-        fmt.write_str("}")?;
-        Ok(())
+        self.relink(nodes);
+        true
     }
-}
 
-impl<'a, T> Node<'a, T> {
-    pub fn last(root: Rc<Node<T>>) -> Rc<Node<T>> {
-        let mut node = root;
-        while let Some(next) = node.next.take() {
-            *node.next.borrow_mut() = Some(Rc::clone(&next));
-            node = next;
+    // The nodes of the list in forward order.
+    fn nodes(&self) -> Vec<Rc<Node<'a, T>>> {
+        let mut nodes = Vec::new();
+        let mut node = self.root.borrow().clone();
+        while let Some(current) = node {
+            node = current.next();
+            nodes.push(current);
         }
-        Rc::clone(&node)
+        nodes
     }
-}
 
-#[derive(Debug)]
-pub struct NodeIterator<'a, T> {
-    node: Option<Rc<Node<'a, T>>>,
-    reverse: bool,
-}
+    // Chain `nodes` together in the given order and make the first one the root.
+    fn relink(&self, nodes: Vec<Rc<Node<'a, T>>>) {
+        for (i, node) in nodes.iter().enumerate() {
+            *node.prev.borrow_mut() = if i > 0 {
+                Some(Rc::clone(&nodes[i - 1]))
+            } else {
+                None
+            };
+            *node.next.borrow_mut() = nodes.get(i + 1).map(Rc::clone);
+        }
+        *self.root.borrow_mut() = nodes.first().map(Rc::clone);
+    }
 
-// Get the next item in a node iterator.
-// `$key` should be either `prev` or `next`.
-macro_rules! iterate_in_direction {
-    ($self:ident, $key:ident) => {{
-        let node = $self.node.take();
-        match node {
-            Some(node) => {
-                let new_cell = Rc::clone(&node.value);
-                let value = node.$key.take();
-                $self.node = match value {
-                    Some(value) => {
-                        *node.$key.borrow_mut() = Some(Rc::clone(&value));
-                        Some(Rc::clone(&value))
-                    }
-                    None => None,
-                };
-                Some(new_cell)
+    // The middle value in sorted order, taking the lower one for even lengths.
+    pub fn median(&self) -> Option<Rc<T>>
+    where
+        T: Ord + Clone,
+    {
+        let mut values: Vec<Rc<T>> = self.iter().collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort();
+        Some(values.swap_remove((values.len() - 1) / 2))
+    }
+
+    pub fn frequency(&self) -> HashMap<T, usize>
+    where
+        T: Eq + Hash + Clone,
+    {
+        let mut counts = HashMap::new();
+        for value in self.iter() {
+            *counts.entry((*value).clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    // The most frequent value, preferring the earliest one on ties.
+    pub fn mode(&self) -> Option<Rc<T>>
+    where
+        T: Eq + Hash + Clone,
+    {
+        let counts = self.frequency();
+        let mut best: Option<(Rc<T>, usize)> = None;
+        for value in self.iter() {
+            let count = counts[&*value];
+            if best.as_ref().is_none_or(|(_, most)| count > *most) {
+                best = Some((value, count));
             }
-            None => None,
         }
-    }};
-}
+        best.map(|(value, _)| value)
+    }
 
-impl<'a, T> Iterator for NodeIterator<'a, T>
-where
-    T: std::fmt::Debug,
-{
-    type Item = Rc<T>;
+    // Swap every adjacent pair of nodes, leaving a trailing odd node in place.
+    pub fn swap_pairs(&self) {
+        let mut nodes = self.nodes();
+        for pair in nodes.chunks_exact_mut(2) {
+            pair.swap(0, 1);
+        }
+        self.relink(nodes);
+    }
 
-    fn next(&mut self) -> Option<Rc<T>> {
-        match self.reverse {
-            false => {
-                iterate_in_direction!(self, next)
+    // Compare values like `==` would, but also require that both lists have
+    // `prev` links that mirror their `next` links.
+    pub fn structurally_equal(&self, other: &DoublyLinkedList<'a, T>) -> bool
+    where
+        T: PartialEq,
+    {
+        self.links_consistent()
+            && other.links_consistent()
+            && self.iter().eq(other.iter())
+            && self.rev_iter().eq(other.rev_iter())
+    }
+
+    fn links_consistent(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    // Check that every `prev` link mirrors the `next` link pointing at it.
+    pub fn validate(&self) -> Result<(), String> {
+        let nodes = self.nodes();
+        if let Some(root) = nodes.first() {
+            if root.prev.borrow().is_some() {
+                return Err("root node has a prev link".to_string());
             }
-            true => {
-                iterate_in_direction!(self, prev)
+        }
+        for (i, pair) in nodes.windows(2).enumerate() {
+            match &*pair[1].prev.borrow() {
+                Some(prev) if Rc::ptr_eq(prev, &pair[0]) => {}
+                Some(_) => {
+                    return Err(format!(
+                        "node {} has a prev link that does not point at node {}",
+                        i + 1,
+                        i
+                    ))
+                }
+                None => return Err(format!("node {} is missing its prev link", i + 1)),
             }
         }
+        Ok(())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn it_can_debug_a_node() {
-        let node = Node {
-            prev: RefCell::new(None),
-            value: Rc::new(1337),
-            next: RefCell::new(None),
-        };
+    // Validate the list before any cleanup. The `Rc` representation has no
+    // free slots to reclaim, so for it this is the same as `validate`.
+    pub fn compact(&self) -> Result<(), String> {
+        self.validate()
+    }
 
-        assert_eq!(format!("{:?}", node), "1337");
+    // Reorder the list so position `i` holds the node previously at `indices[i]`.
+    // Panics unless `indices` is a permutation of `0..len`.
+    pub fn permute(&self, indices: &[usize]) {
+        let nodes = self.nodes();
+        assert_eq!(
+            indices.len(),
+            nodes.len(),
+            "permutation length must match list length"
+        );
+        let mut seen = vec![false; nodes.len()];
+        for &index in indices {
+            assert!(
+                index < nodes.len() && !seen[index],
+                "indices must be a permutation of 0..{}",
+                nodes.len()
+            );
+            seen[index] = true;
+        }
+        self.relink(
+            indices
+                .iter()
+                .map(|&index| Rc::clone(&nodes[index]))
+                .collect(),
+        );
     }
 
-    #[test]
-    fn it_can_debug_a_doubly_linked_node() {
-        let first = Rc::new(Node {
-            prev: RefCell::new(None),
-            value: Rc::new("first"),
-            next: RefCell::new(None),
-        });
-        let second = Rc::new(Node {
-            prev: RefCell::new(Some(Rc::clone(&first))),
-            value: Rc::new("second"),
-            next: RefCell::new(None),
-        });
-        *first.next.borrow_mut() = Some(Rc::clone(&second));
+    pub fn push_back(&self, value: T) {
+        self.push_back_many(std::iter::once(value));
+    }
 
-        assert_eq!(format!("{:?}", first), "\"first\",\n    \"second\"");
+    // Append every value, walking to the tail only once.
+    pub fn push_back_many<I: IntoIterator<Item = T>>(&self, values: I) {
+        let root = self.root.borrow().clone();
+        let mut tail = root.map(Node::last);
+        for value in values {
+            let node = Node::new(Rc::new(value));
+            match &tail {
+                Some(last) => {
+                    *node.prev.borrow_mut() = Some(Rc::clone(last));
+                    *last.next.borrow_mut() = Some(Rc::clone(&node));
+                }
+                None => *self.root.borrow_mut() = Some(Rc::clone(&node)),
+            }
+            tail = Some(node);
+        }
     }
 
-    #[test]
-    fn it_can_debug_a_linked_list() {
-        let list = doubly_linked_list!("first", "second", "third", "fourth");
+    // Append the items of `iter` onto an existing list.
+    pub fn collect_into<I: IntoIterator<Item = T>>(target: &DoublyLinkedList<'a, T>, iter: I) {
+        target.push_back_many(iter);
+    }
 
-        assert_eq!(format!("{:?}", list), "DoublyLinkedList {\n    \"first\",\n    \"second\",\n    \"third\",\n    \"fourth\"\n}");
+    // Handle to the last node whose value matches, searching from the tail.
+    pub fn rfind_handle<F: FnMut(&T) -> bool>(
+        &self,
+        mut predicate: F,
+    ) -> Option<NodeHandle<'a, T>> {
+        let root = self.root.borrow().clone();
+        let mut node = root.map(Node::last);
+        while let Some(current) = node {
+            if predicate(&current.value()) {
+                return Some(NodeHandle { node: current });
+            }
+            node = current.prev();
+        }
+        None
     }
 
-    #[test]
-    fn it_can_iterate_over_the_items() {
-        let list = doubly_linked_list!(1, 2);
+    // Unlink the handle's node from this list and return its value. Panics if
+    // the node was already removed or is the head of another list.
+    pub fn remove_node(&self, handle: &NodeHandle<'a, T>) -> Rc<T> {
+        assert!(self.is_linked(&handle.node), "handle is not in this list");
+        self.unlink(&handle.node);
+        handle.node.value()
+    }
 
-        let mut out = Vec::<i32>::new();
+    // An O(1) check that `node` is linked in: either it is this list's root,
+    // or its predecessor still points back at it. A removed node has neither,
+    // but an interior node of another list cannot be told apart this way.
+    fn is_linked(&self, node: &Rc<Node<'a, T>>) -> bool {
+        match node.prev() {
+            Some(prev) => prev.next().is_some_and(|next| Rc::ptr_eq(&next, node)),
+            None => self
+                .root
+                .borrow()
+                .as_ref()
+                .is_some_and(|root| Rc::ptr_eq(root, node)),
+        }
+    }
 
-        for i in list.iter() {
-            out.push(*i.clone());
+    fn unlink(&self, node: &Rc<Node<'a, T>>) {
+        let prev = node.prev();
+        let next = node.next();
+        match &prev {
+            Some(prev) => *prev.next.borrow_mut() = next.clone(),
+            None => *self.root.borrow_mut() = next.clone(),
+        }
+        if let Some(next) = &next {
+            *next.prev.borrow_mut() = prev;
         }
+        node.detach();
+    }
 
-        assert_eq!(out, vec![1, 2]);
+    // The values at the indices in `range`. Panics if the range is out of bounds.
+    pub fn values_between(&self, range: Range<usize>) -> Vec<Rc<T>> {
+        Self::check_range(&range, self.len());
+        self.iter()
+            .skip(range.start)
+            .take(range.end - range.start)
+            .collect()
     }
 
-    #[test]
-    fn it_can_iterate_over_the_items_twice() {
-        let list = doubly_linked_list!(1, 2);
+    // For a sorted list that has been rotated, the index where it wraps around
+    // back to its smallest value. `None` if the list is already sorted or is not
+    // a rotation of a sorted list.
+    pub fn rotation_point(&self) -> Option<usize>
+    where
+        T: Ord,
+    {
+        let values: Vec<Rc<T>> = self.iter().collect();
+        let mut descents = (1..values.len()).filter(|&i| values[i - 1] > values[i]);
+        let point = descents.next()?;
+        if descents.next().is_some() || values[values.len() - 1] > values[0] {
+            return None;
+        }
+        Some(point)
+    }
 
-        let mut first = Vec::<i32>::new();
-        let mut second = Vec::<i32>::new();
+    // Rotate a rotated sorted list back into ascending order. Lists that are
+    // already sorted, or are not a rotated sorted sequence, are left unchanged.
+    pub fn normalize_rotation(&self)
+    where
+        T: Ord,
+    {
+        if let Some(point) = self.rotation_point() {
+            self.rotate_left(point);
+        }
+    }
 
-        for i in list.iter() {
-            first.push(*i.clone());
+    // Whether `rev_iter` yields exactly the reverse of `iter`.
+    pub fn directions_consistent(&self) -> bool
+    where
+        T: PartialEq,
+    {
+        let forward: Vec<Rc<T>> = self.iter().collect();
+        let backward: Vec<Rc<T>> = self.rev_iter().collect();
+        forward.iter().eq(backward.iter().rev())
+    }
+
+    // Move all nodes of `other` onto the end of this list.
+    pub fn append(&self, other: DoublyLinkedList<'a, T>) {
+        let other_root = match other.root.take() {
+            Some(root) => root,
+            None => return,
+        };
+        let root = self.root.borrow().clone();
+        match root.map(Node::last) {
+            Some(last) => {
+                *other_root.prev.borrow_mut() = Some(Rc::clone(&last));
+                *last.next.borrow_mut() = Some(other_root);
+            }
+            None => *self.root.borrow_mut() = Some(other_root),
         }
+    }
 
-        for i in list.iter() {
-            second.push(*i.clone());
+    // Append clones of `other`'s values, leaving `other` untouched. The values
+    // are collected first so that `other` may be this same list.
+    pub fn append_cloned(&self, other: &DoublyLinkedList<'a, T>)
+    where
+        T: Clone,
+    {
+        let values: Vec<T> = other.iter().map(|value| (*value).clone()).collect();
+        self.push_back_many(values);
+    }
+
+    // Handle/value pairs from head to tail. The iterator steps past a node
+    // before yielding it, so the yielded node may be removed while iterating.
+    pub fn iter_with_handles(&self) -> impl Iterator<Item = (NodeHandle<'a, T>, Rc<T>)> {
+        let mut node = self.root.borrow().clone();
+        std::iter::from_fn(move || {
+            let current = node.take()?;
+            node = current.next();
+            let value = current.value();
+            Some((NodeHandle { node: current }, value))
+        })
+    }
+
+    // Like `iter_with_handles`, but from tail to head.
+    pub fn iter_handles_rev(&self) -> impl Iterator<Item = (NodeHandle<'a, T>, Rc<T>)> {
+        let root = self.root.borrow().clone();
+        let mut node = root.map(Node::last);
+        std::iter::from_fn(move || {
+            let current = node.take()?;
+            node = current.prev();
+            let value = current.value();
+            Some((NodeHandle { node: current }, value))
+        })
+    }
+
+    // Insert all `values` starting at `index`, in order. Inserting at the
+    // length appends; a larger index panics.
+    pub fn insert_many<I: IntoIterator<Item = T>>(&self, index: usize, values: I) {
+        let mut prev: Option<Rc<Node<'a, T>>> = None;
+        let mut next = self.root.borrow().clone();
+        for position in 0..index {
+            let current = next.unwrap_or_else(|| {
+                panic!(
+                    "insertion index {} out of bounds for length {}",
+                    index, position
+                )
+            });
+            next = current.next();
+            prev = Some(current);
+        }
+        for value in values {
+            let node = Node::new(Rc::new(value));
+            match &prev {
+                Some(prev) => {
+                    *node.prev.borrow_mut() = Some(Rc::clone(prev));
+                    *prev.next.borrow_mut() = Some(Rc::clone(&node));
+                }
+                None => *self.root.borrow_mut() = Some(Rc::clone(&node)),
+            }
+            prev = Some(node);
+        }
+        if let (Some(prev), Some(next)) = (prev, next) {
+            *next.prev.borrow_mut() = Some(Rc::clone(&prev));
+            *prev.next.borrow_mut() = Some(next);
         }
+    }
 
-        assert_eq!([first, second], [vec![1, 2], vec![1, 2]]);
+    pub fn first_n(&self, n: usize) -> Vec<Rc<T>> {
+        self.iter().take(n).collect()
     }
 
-    #[test]
-    fn it_can_get_the_last_node() {
-        let list = doubly_linked_list!(1, 2);
-        let root = list.root.take().unwrap();
-        let last = Node::last(root);
+    // Up to the last `n` values, in forward order.
+    pub fn last_n(&self, n: usize) -> Vec<Rc<T>> {
+        let mut values: Vec<Rc<T>> = self.rev_iter().take(n).collect();
+        values.reverse();
+        values
+    }
 
-        assert_eq!(last.value, Rc::new(2));
+    // Reverse the nodes from `a` through `b` inclusive. Panics unless both
+    // belong to this list and `a` does not come after `b`.
+    pub fn reverse_between(&self, a: &NodeHandle<'a, T>, b: &NodeHandle<'a, T>) {
+        let mut nodes = self.nodes();
+        let start = Self::position_of(&nodes, a).expect("handle `a` is not in this list");
+        let end = Self::position_of(&nodes, b).expect("handle `b` is not in this list");
+        assert!(start <= end, "handle `a` must precede handle `b`");
+        nodes[start..=end].reverse();
+        self.relink(nodes);
     }
 
-    #[test]
-    fn it_can_reverse_iterate_over_the_items() {
-        let list = doubly_linked_list!(1, 2);
+    fn check_range(range: &Range<usize>, len: usize) {
+        assert!(
+            range.start <= range.end && range.end <= len,
+            "range {:?} out of bounds for length {}",
+            range,
+            len
+        );
+    }
 
-        let mut out = Vec::<i32>::new();
+    fn position_of(nodes: &[Rc<Node<'a, T>>], handle: &NodeHandle<'a, T>) -> Option<usize> {
+        nodes.iter().position(|node| Rc::ptr_eq(node, &handle.node))
+    }
 
-        for i in list.rev_iter() {
-            out.push(*i.clone());
+    // The value `offset` nodes after (or, when negative, before) the handle's
+    // node, or `None` if that walks off either end.
+    pub fn value_at_offset(&self, handle: &NodeHandle<'a, T>, offset: isize) -> Option<Rc<T>> {
+        let mut node = Rc::clone(&handle.node);
+        for _ in 0..offset.unsigned_abs() {
+            node = if offset > 0 { node.next() } else { node.prev() }?;
         }
+        Some(node.value())
+    }
 
-        assert_eq!(out, vec![2, 1]);
+    // Unlink matching values from both ends until a non-matching value is
+    // reached on each side.
+    pub fn trim<F: FnMut(&T) -> bool>(&self, mut f: F) {
+        self.trim_front(&mut f);
+        self.trim_back(f);
     }
 
-    #[test]
-    fn it_can_reverse_iterate_over_the_items_multiple_times() {
-        let list = doubly_linked_list!(1, 2);
+    pub fn trim_front<F: FnMut(&T) -> bool>(&self, mut f: F) {
+        loop {
+            let root = self.root.borrow().clone();
+            match root {
+                Some(node) if f(&node.value()) => self.unlink(&node),
+                _ => break,
+            }
+        }
+    }
 
-        let mut first = Vec::<i32>::new();
-        let mut second = Vec::<i32>::new();
+    pub fn trim_back<F: FnMut(&T) -> bool>(&self, mut f: F) {
+        let root = self.root.borrow().clone();
+        let mut tail = root.map(Node::last);
+        while let Some(node) = tail {
+            if !f(&node.value()) {
+                break;
+            }
+            tail = node.prev();
+            self.unlink(&node);
+        }
+    }
 
-        for i in list.rev_iter() {
-            first.push(*i.clone());
+    // Call `f` with each sliding window of `size` values, reusing one buffer
+    // instead of allocating per window like `windows` does.
+    pub fn for_each_window<F: FnMut(&[Rc<T>])>(&self, size: usize, mut f: F) {
+        assert!(size > 0, "window size must be non-zero");
+        let mut window = Vec::with_capacity(size);
+        for value in self.iter() {
+            if window.len() == size {
+                window.remove(0);
+            }
+            window.push(value);
+            if window.len() == size {
+                f(&window);
+            }
         }
+    }
 
-        for i in list.rev_iter() {
-            second.push(*i.clone());
+    pub fn eq_slice(&self, slice: &[T]) -> bool
+    where
+        T: PartialEq,
+    {
+        let mut values = self.iter();
+        slice
+            .iter()
+            .all(|expected| values.next().is_some_and(|value| *value == *expected))
+            && values.next().is_none()
+    }
+
+    // Values fanning out from the handle's node: the node itself, then
+    // alternately one step further forward and one step further back, until
+    // both directions run out.
+    pub fn iter_from_both(&self, handle: &NodeHandle<'a, T>) -> impl Iterator<Item = Rc<T>> + 'a
+    where
+        T: 'a,
+    {
+        let mut center = Some(Rc::clone(&handle.node));
+        let mut forward = handle.node.next();
+        let mut backward = handle.node.prev();
+        let mut forward_turn = true;
+        std::iter::from_fn(move || {
+            if let Some(node) = center.take() {
+                return Some(node.value());
+            }
+            if forward.is_none() {
+                forward_turn = false;
+            } else if backward.is_none() {
+                forward_turn = true;
+            }
+            let node = if forward_turn {
+                let node = forward.take()?;
+                forward = node.next();
+                node
+            } else {
+                let node = backward.take()?;
+                backward = node.prev();
+                node
+            };
+            forward_turn = !forward_turn;
+            Some(node.value())
+        })
+    }
+
+    // Unlink and yield values from the tail towards the head. Whatever has not
+    // been yielded when the iterator is dropped is removed as well.
+    pub fn drain_back(&self) -> DrainBack<'_, 'a, T> {
+        let root = self.root.borrow().clone();
+        DrainBack {
+            list: self,
+            tail: root.map(Node::last),
         }
+    }
 
-        assert_eq!([first, second], [vec![2, 1], vec![2, 1]]);
+    // Store `new` in the handle's node and return the value it replaced.
+    pub fn swap_value(&self, handle: &NodeHandle<'a, T>, new: T) -> Rc<T> {
+        handle.node.value.replace(Rc::new(new))
+    }
+
+    pub fn contains_subsequence(&self, pattern: &[T]) -> bool
+    where
+        T: PartialEq,
+    {
+        self.find_subsequence(pattern).is_some()
+    }
+
+    // Remove the node at `index` and return its value. Panics if out of bounds.
+    pub fn remove(&self, index: usize) -> Rc<T> {
+        self.try_remove(index)
+            .unwrap_or_else(|| panic!("removal index {} out of bounds", index))
+    }
+
+    // Like `remove`, but returns `None` for an out of bounds index.
+    pub fn try_remove(&self, index: usize) -> Option<Rc<T>> {
+        let (handle, _) = self.iter_with_handles().nth(index)?;
+        Some(self.remove_node(&handle))
+    }
+
+    // Insert `value` at `index`. Inserting at the length appends; a larger
+    // index panics.
+    pub fn insert(&self, index: usize, value: T) {
+        self.insert_many(index, std::iter::once(value));
+    }
+
+    // Like `insert`, but hands `value` back for an out of bounds index.
+    pub fn try_insert(&self, index: usize, value: T) -> Result<(), T> {
+        if index > self.len() {
+            return Err(value);
+        }
+        self.insert(index, value);
+        Ok(())
+    }
+
+    // Walk from the tail, pairing each value with its index from the head.
+    // Removing the yielded index while iterating is safe, as it only shifts
+    // nodes that have already been visited.
+    pub fn rev_iter_indexed(&self) -> impl Iterator<Item = (usize, Rc<T>)> + 'a
+    where
+        T: 'a,
+    {
+        let len = self.len();
+        self.rev_iter()
+            .enumerate()
+            .map(move |(i, value)| (len - 1 - i, value))
+    }
+
+    // The `Rc` holding the value at `index`, shared with the list rather than
+    // copied out of it.
+    pub fn share(&self, index: usize) -> Option<Rc<T>> {
+        self.iter().nth(index)
+    }
+
+    // Collapse each run of consecutive values with equal keys down to the one
+    // with the largest `weight`, keeping the first one on ties. Every value in
+    // a run shares its key, so the key alone cannot pick the heaviest value;
+    // that takes the separate `weight` function.
+    pub fn dedup_keep_max_by_key<K, W, F, G>(&self, mut key: F, mut weight: G)
+    where
+        K: Ord,
+        W: Ord,
+        F: FnMut(&T) -> K,
+        G: FnMut(&T) -> W,
+    {
+        let mut run: Option<(K, W, Rc<Node<'a, T>>)> = None;
+        for node in self.nodes() {
+            let node_key = key(&node.value());
+            let node_weight = weight(&node.value());
+            match run.take() {
+                Some((run_key, kept_weight, kept)) if run_key == node_key => {
+                    if node_weight > kept_weight {
+                        self.unlink(&kept);
+                        run = Some((run_key, node_weight, node));
+                    } else {
+                        self.unlink(&node);
+                        run = Some((run_key, kept_weight, kept));
+                    }
+                }
+                _ => run = Some((node_key, node_weight, node)),
+            }
+        }
+    }
+
+    pub fn to_hash_set(&self) -> HashSet<T>
+    where
+        T: Eq + Hash + Clone,
+    {
+        self.iter().map(|value| (*value).clone()).collect()
+    }
+
+    pub fn to_btree_set(&self) -> BTreeSet<T>
+    where
+        T: Ord + Clone,
+    {
+        self.iter().map(|value| (*value).clone()).collect()
+    }
+
+    // Values found in exactly one of the two lists, each listed once in order
+    // of first appearance, with `self`'s values ahead of `other`'s.
+    pub fn symmetric_difference(&self, other: &DoublyLinkedList<'a, T>) -> DoublyLinkedList<'a, T>
+    where
+        T: Eq + Hash + Clone,
+    {
+        let ours = self.to_hash_set();
+        let theirs = other.to_hash_set();
+        let mut seen = HashSet::new();
+        self.iter()
+            .filter(|value| !theirs.contains(&**value))
+            .chain(other.iter().filter(|value| !ours.contains(&**value)))
+            .filter(|value| seen.insert(Rc::clone(value)))
+            .map(|value| (*value).clone())
+            .collect()
+    }
+
+    // Values found in both lists, each listed once in `self`'s order.
+    pub fn intersection(&self, other: &DoublyLinkedList<'a, T>) -> DoublyLinkedList<'a, T>
+    where
+        T: Eq + Hash + Clone,
+    {
+        let theirs = other.to_hash_set();
+        let mut seen = HashSet::new();
+        self.iter()
+            .filter(|value| theirs.contains(&**value) && seen.insert(Rc::clone(value)))
+            .map(|value| (*value).clone())
+            .collect()
+    }
+
+    // Values of `self` that are not in `other`, in `self`'s order.
+    pub fn difference(&self, other: &DoublyLinkedList<'a, T>) -> DoublyLinkedList<'a, T>
+    where
+        T: Eq + Hash + Clone,
+    {
+        let theirs = other.to_hash_set();
+        self.iter()
+            .filter(|value| !theirs.contains(&**value))
+            .map(|value| (*value).clone())
+            .collect()
+    }
+
+    // Each run of consecutive equal values as a `(value, count)` pair.
+    pub fn run_length_encode(&self) -> Vec<(Rc<T>, usize)>
+    where
+        T: PartialEq,
+    {
+        let mut runs: Vec<(Rc<T>, usize)> = Vec::new();
+        for value in self.iter() {
+            match runs.last_mut() {
+                Some((last, count)) if *last == value => *count += 1,
+                _ => runs.push((value, 1)),
+            }
+        }
+        runs
+    }
+
+    // Iterate while skipping values equal to the one just yielded.
+    pub fn iter_dedup(&self) -> impl Iterator<Item = Rc<T>> + 'a
+    where
+        T: PartialEq + 'a,
+    {
+        let mut last: Option<Rc<T>> = None;
+        self.iter().filter(move |value| {
+            if last.as_ref() == Some(value) {
+                return false;
+            }
+            last = Some(Rc::clone(value));
+            true
+        })
+    }
+
+    // A new list whose value `i` is the sum of the first `i + 1` values.
+    pub fn prefix_sums(&self) -> DoublyLinkedList<'a, T>
+    where
+        T: Add<Output = T> + Clone,
+    {
+        let mut total: Option<T> = None;
+        self.iter()
+            .map(|value| {
+                let sum = match total.take() {
+                    Some(sum) => sum + (*value).clone(),
+                    None => (*value).clone(),
+                };
+                total = Some(sum.clone());
+                sum
+            })
+            .collect()
+    }
+
+    // A new list of the differences between each pair of neighbouring values.
+    pub fn differences(&self) -> DoublyLinkedList<'a, T>
+    where
+        T: Sub<Output = T> + Clone,
+    {
+        let values: Vec<Rc<T>> = self.iter().collect();
+        values
+            .windows(2)
+            .map(|pair| (*pair[1]).clone() - (*pair[0]).clone())
+            .collect()
+    }
+
+    // Move the first `n` nodes to the back, wrapping around for `n >= len`.
+    pub fn rotate_left(&self, n: usize) {
+        self.rotate_by_signed(self.rotation_delta(n));
+    }
+
+    // Move the last `n` nodes to the front, wrapping around for `n >= len`.
+    pub fn rotate_right(&self, n: usize) {
+        self.rotate_by_signed(-self.rotation_delta(n));
+    }
+
+    // `n` reduced modulo the length, so it fits an `isize` and can be negated.
+    fn rotation_delta(&self, n: usize) -> isize {
+        n.checked_rem(self.len()).unwrap_or(0) as isize
+    }
+
+    // Rotate left by `delta` nodes, or right when it is negative.
+    fn rotate_by_signed(&self, delta: isize) {
+        let mut nodes = self.nodes();
+        if nodes.is_empty() {
+            return;
+        }
+        let amount = delta.rem_euclid(nodes.len() as isize) as usize;
+        if amount == 0 {
+            return;
+        }
+        nodes.rotate_left(amount);
+        self.relink(nodes);
+    }
+
+    // A read-only cursor starting at the head of the list.
+    pub fn cursor(&self) -> Cursor<'a, T> {
+        Cursor {
+            node: self.root.borrow().clone(),
+        }
+    }
+
+    // The values in forward and in reverse order, from a single forward walk.
+    pub fn snapshots(&self) -> (Vec<Rc<T>>, Vec<Rc<T>>) {
+        let forward: Vec<Rc<T>> = self.iter().collect();
+        let backward = forward.iter().rev().map(Rc::clone).collect();
+        (forward, backward)
+    }
+
+    // Copy the values into the contiguous arena representation.
+    pub fn to_arena(&self) -> ArenaDoublyLinkedList<T>
+    where
+        T: Clone,
+    {
+        let mut arena = ArenaDoublyLinkedList::with_capacity(self.len());
+        for value in self.iter() {
+            arena.push_back((*value).clone());
+        }
+        arena
+    }
+
+    // Rebuild an `Rc`-backed list from the arena representation.
+    pub fn from_arena(arena: &ArenaDoublyLinkedList<T>) -> DoublyLinkedList<'a, T>
+    where
+        T: Clone,
+    {
+        arena.iter().cloned().collect()
+    }
+
+    // Build a list from fallible items, stopping at and returning the first
+    // error. The nodes linked before the error are freed.
+    pub fn try_from_iter<E, I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, E> {
+        let list = DoublyLinkedList::new();
+        let mut error = None;
+        list.push_back_many(iter.into_iter().map_while(|item| match item {
+            Ok(value) => Some(value),
+            Err(err) => {
+                error = Some(err);
+                None
+            }
+        }));
+        match error {
+            Some(err) => Err(err),
+            None => Ok(list),
+        }
+    }
+
+    // Run `f` on every value for its side effects, returning the list for chaining.
+    pub fn inspect_each<F: FnMut(&T)>(&self, mut f: F) -> &Self {
+        for value in self.iter() {
+            f(&value);
+        }
+        self
+    }
+
+    // Stably sort the list by rewiring its nodes.
+    pub fn sort(&self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    // Stably sort the list with a comparator, like `slice::sort_by`.
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&self, mut compare: F) {
+        let mut nodes = self.nodes();
+        nodes.sort_by(|a, b| compare(&a.value(), &b.value()));
+        self.relink(nodes);
+    }
+
+    // Stably sort the list by a key, like `slice::sort_by_key`.
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&self, mut key: F) {
+        self.sort_by(|a, b| key(a).cmp(&key(b)));
+    }
+
+    // Unlink every value equal to the one before it.
+    pub fn dedup(&self)
+    where
+        T: PartialEq,
+    {
+        for pair in self.nodes().windows(2) {
+            if pair[0].value() == pair[1].value() {
+                self.unlink(&pair[0]);
+            }
+        }
+    }
+
+    pub fn sort_dedup(&self)
+    where
+        T: Ord,
+    {
+        self.sort();
+        self.dedup();
+    }
+
+    // Consume the list, taking each value out of its `Rc` where the list held
+    // the only reference and handing back the shared `Rc` otherwise. A live
+    // `NodeHandle` keeps its node, and so the node's reference to the value,
+    // alive as well: that value comes back as `Err` even if nothing else
+    // shares it.
+    pub fn into_value_results(self) -> Vec<Result<T, Rc<T>>> {
+        let nodes = self.nodes();
+        self.root.take();
+        for node in &nodes {
+            node.detach();
+        }
+        nodes
+            .into_iter()
+            .map(|node| match Rc::try_unwrap(node) {
+                Ok(node) => Rc::try_unwrap(node.value.into_inner()),
+                Err(node) => Err(node.value()),
+            })
+            .collect()
+    }
+
+    // Rotate so the first largest value becomes the head.
+    pub fn rotate_to_max(&self)
+    where
+        T: Ord,
+    {
+        let mut best: Option<(usize, Rc<T>)> = None;
+        for (i, value) in self.iter().enumerate() {
+            if best.as_ref().is_none_or(|(_, max)| value > *max) {
+                best = Some((i, value));
+            }
+        }
+        if let Some((i, _)) = best {
+            self.rotate_left(i);
+        }
+    }
+
+    // Where `value` would be inserted to keep a sorted list sorted: the index
+    // of the first value greater than it.
+    pub fn insertion_index(&self, value: &T) -> usize
+    where
+        T: Ord,
+    {
+        self.iter()
+            .position(|current| *current > *value)
+            .unwrap_or_else(|| self.len())
+    }
+
+    // Each run of consecutive equal values as a list of its own.
+    pub fn runs(&self) -> Vec<DoublyLinkedList<'a, T>>
+    where
+        T: PartialEq + Clone,
+    {
+        self.run_length_encode()
+            .into_iter()
+            .map(|(value, count)| std::iter::repeat_n((*value).clone(), count).collect())
+            .collect()
+    }
+
+    // Call `f` with a handle and a mutable reference to each value in turn.
+    // `f` works on a clone that replaces the node's value once it returns, so
+    // it may read the list, and other holders of the value keep the old one.
+    pub fn for_each_mut_with_handles<F: FnMut(&NodeHandle<'a, T>, &mut T)>(&self, mut f: F)
+    where
+        T: Clone,
+    {
+        for (handle, value) in self.iter_with_handles() {
+            let mut value = (*value).clone();
+            f(&handle, &mut value);
+            handle.node.value.replace(Rc::new(value));
+        }
+    }
+
+    // Consume the list into a `Vec` of its values in reverse order, walking
+    // back from the tail once.
+    pub fn into_reversed_vec(self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.rev_iter().map(|value| (*value).clone()).collect()
+    }
+
+    // Display the list with custom delimiters, e.g. `list.format("(", ")", "; ")`.
+    pub fn format<'b>(
+        &'b self,
+        open: &'static str,
+        close: &'static str,
+        sep: &'static str,
+    ) -> ListFormat<'a, 'b, T> {
+        ListFormat {
+            list: self,
+            open,
+            close,
+            sep,
+        }
+    }
+
+    // Rotate only the nodes in `range` left by `n`, wrapping around within the
+    // window. Panics if the range is out of bounds.
+    pub fn rotate_range(&self, range: Range<usize>, n: usize) {
+        let mut nodes = self.nodes();
+        Self::check_range(&range, nodes.len());
+        if range.is_empty() {
+            return;
+        }
+        let window = &mut nodes[range];
+        let amount = n % window.len();
+        window.rotate_left(amount);
+        self.relink(nodes);
+    }
+
+    // Walk the list merging neighbours: whenever `combine` returns a value for
+    // the current node and the next one, the pair is replaced by a single node
+    // holding it, which is then compared with the node after.
+    pub fn squash<F: FnMut(&T, &T) -> Option<T>>(&self, mut combine: F) {
+        let mut node = self.root.borrow().clone();
+        while let Some(current) = node {
+            let next = match current.next() {
+                Some(next) => next,
+                None => break,
+            };
+            match combine(&current.value(), &next.value()) {
+                Some(merged) => {
+                    current.value.replace(Rc::new(merged));
+                    self.unlink(&next);
+                    node = Some(current);
+                }
+                None => node = Some(next),
+            }
+        }
+    }
+
+    // Remove the nodes at `indices`, which may be unsorted and repeat, in one
+    // walk. Returns the removed values by ascending index and panics, before
+    // removing anything, if any index is out of bounds.
+    pub fn remove_indices(&self, indices: &[usize]) -> Vec<Rc<T>> {
+        let nodes = self.nodes();
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        if let Some(&last) = indices.last() {
+            assert!(
+                last < nodes.len(),
+                "removal index {} out of bounds for length {}",
+                last,
+                nodes.len()
+            );
+        }
+        indices
+            .into_iter()
+            .map(|index| {
+                self.unlink(&nodes[index]);
+                nodes[index].value()
+            })
+            .collect()
+    }
+
+    // Keep only the nodes at `indices`, in their current order. Panics, before
+    // removing anything, if any index is out of bounds.
+    pub fn retain_indices(&self, indices: &[usize]) {
+        let nodes = self.nodes();
+        let mut keep = vec![false; nodes.len()];
+        for &index in indices {
+            assert!(
+                index < nodes.len(),
+                "index {} out of bounds for length {}",
+                index,
+                nodes.len()
+            );
+            keep[index] = true;
+        }
+        for (node, keep) in nodes.iter().zip(keep) {
+            if !keep {
+                self.unlink(node);
+            }
+        }
+    }
+
+    pub fn push_front(&self, value: T) {
+        self.insert(0, value);
+    }
+
+    // Unlink the tail node and return its value.
+    pub fn pop_back(&self) -> Option<Rc<T>> {
+        let root = self.root.borrow().clone();
+        let last = root.map(Node::last)?;
+        self.unlink(&last);
+        Some(last.value())
+    }
+
+    pub fn front_handle(&self) -> Option<NodeHandle<'a, T>> {
+        let root = self.root.borrow().clone();
+        root.map(|node| NodeHandle { node })
+    }
+
+    // Move the handle's node to the head of the list. Panics if the node was
+    // already removed or is the head of another list.
+    pub fn move_to_front(&self, handle: &NodeHandle<'a, T>) {
+        assert!(self.is_linked(&handle.node), "handle is not in this list");
+        let root = self.root.borrow().clone();
+        if let Some(root) = root {
+            if Rc::ptr_eq(&root, &handle.node) {
+                return;
+            }
+            self.unlink(&handle.node);
+            *handle.node.next.borrow_mut() = Some(Rc::clone(&root));
+            *root.prev.borrow_mut() = Some(Rc::clone(&handle.node));
+            *self.root.borrow_mut() = Some(Rc::clone(&handle.node));
+        }
+    }
+
+    // Rotate the list so the handle's node becomes the head. Panics if the
+    // handle does not belong to this list.
+    pub fn set_head(&self, handle: &NodeHandle<'a, T>) {
+        let nodes = self.nodes();
+        let index = Self::position_of(&nodes, handle).expect("handle is not in this list");
+        self.rotate_left(index);
+    }
+
+    // Yield the values in complete batches of `N`, dropping a trailing
+    // partial batch.
+    pub fn iter_batched<const N: usize>(&self) -> impl Iterator<Item = [Rc<T>; N]> + 'a
+    where
+        T: 'a,
+    {
+        assert!(N > 0, "batch size must be non-zero");
+        let mut values = self.iter();
+        std::iter::from_fn(move || {
+            let batch: [Option<Rc<T>>; N] = std::array::from_fn(|_| values.next());
+            batch[N - 1].as_ref()?;
+            Some(batch.map(|value| value.expect("batch is complete")))
+        })
+    }
+
+    // Prepend all values, keeping them in iteration order ahead of the
+    // existing ones: prepending `[1, 2]` to `[3]` gives `[1, 2, 3]`. See
+    // `extend_front_reversed` for the opposite order.
+    pub fn push_front_many<I: IntoIterator<Item = T>>(&self, values: I) {
+        self.insert_many(0, values);
+    }
+
+    // Check the list against `expected` in both directions and validate its
+    // links, describing the first discrepancy found.
+    pub fn assert_consistent_with(&self, expected: &[T]) -> Result<(), String>
+    where
+        T: PartialEq + Debug,
+    {
+        let forward: Vec<Rc<T>> = self.iter().collect();
+        if let Some(i) = (0..forward.len().max(expected.len()))
+            .find(|&i| forward.get(i).map(|value| &**value) != expected.get(i))
+        {
+            return Err(format!(
+                "forward index {}: expected {:?}, found {:?}",
+                i,
+                expected.get(i),
+                forward.get(i)
+            ));
+        }
+        let backward: Vec<Rc<T>> = self.rev_iter().collect();
+        if let Some(i) = (0..backward.len().max(expected.len()))
+            .find(|&i| backward.get(i).map(|value| &**value) != expected.iter().rev().nth(i))
+        {
+            return Err(format!(
+                "reverse index {}: expected {:?}, found {:?}",
+                i,
+                expected.iter().rev().nth(i),
+                backward.get(i)
+            ));
+        }
+        self.validate()
+    }
+
+    // Replace every value with `f` applied to it, keeping the existing nodes.
+    // Values sit behind a shared `Rc`, so each one is cloned out to be passed
+    // to `f`.
+    pub fn map_in_place<F: FnMut(T) -> T>(&self, mut f: F)
+    where
+        T: Clone,
+    {
+        for node in self.nodes() {
+            let value = f((*node.value()).clone());
+            node.value.replace(Rc::new(value));
+        }
+    }
+
+    // How many leading values the two lists have in common.
+    pub fn common_prefix_len(&self, other: &DoublyLinkedList<'a, T>) -> usize
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .zip(other.iter())
+            .take_while(|(a, b)| a == b)
+            .count()
+    }
+
+    // A new list of `f` applied to pairs of values, stopping at the shorter list.
+    pub fn zip_with<U, V, F>(
+        &self,
+        other: &DoublyLinkedList<'a, U>,
+        mut f: F,
+    ) -> DoublyLinkedList<'a, V>
+    where
+        F: FnMut(&T, &U) -> V,
+    {
+        self.iter()
+            .zip(other.iter())
+            .map(|(a, b)| f(&a, &b))
+            .collect()
+    }
+
+    // Copies of the values before the first match and of the values from the
+    // match onward, or `None` if `value` is not in the list.
+    pub fn split_at_value(
+        &self,
+        value: &T,
+    ) -> Option<(DoublyLinkedList<'a, T>, DoublyLinkedList<'a, T>)>
+    where
+        T: PartialEq + Clone,
+    {
+        let index = self.iter().position(|current| *current == *value)?;
+        let mut values = self.iter().map(|value| (*value).clone());
+        let before = values.by_ref().take(index).collect();
+        Some((before, values.collect()))
+    }
+
+    // The number of maximal runs of consecutive equal values.
+    pub fn count_runs(&self) -> usize
+    where
+        T: PartialEq,
+    {
+        self.run_length_encode().len()
+    }
+
+    // Whether the list reads the same in both directions, comparing from both
+    // ends inward until they meet.
+    pub fn is_palindrome(&self) -> bool
+    where
+        T: PartialEq,
+    {
+        let root = self.root.borrow().clone();
+        let (mut front, mut back) = match root {
+            Some(root) => (Rc::clone(&root), Node::last(root)),
+            None => return true,
+        };
+        loop {
+            if Rc::ptr_eq(&front, &back) {
+                return true;
+            }
+            if front.value() != back.value() {
+                return false;
+            }
+            if front.next().is_some_and(|next| Rc::ptr_eq(&next, &back)) {
+                return true;
+            }
+            match (front.next(), back.prev()) {
+                (Some(next), Some(prev)) => {
+                    front = next;
+                    back = prev;
+                }
+                _ => return true,
+            }
+        }
+    }
+
+    // A new list holding the lexicographically smallest rotation of this one.
+    pub fn min_rotation(&self) -> DoublyLinkedList<'a, T>
+    where
+        T: Ord + Clone,
+    {
+        let values: Vec<Rc<T>> = self.iter().collect();
+        let rotation = |start: usize| values[start..].iter().chain(&values[..start]);
+        let best = (0..values.len())
+            .min_by(|&a, &b| rotation(a).cmp(rotation(b)))
+            .unwrap_or(0);
+        rotation(best).map(|value| (**value).clone()).collect()
+    }
+
+    // Unlink the head node and return its value.
+    pub fn pop_front(&self) -> Option<Rc<T>> {
+        let root = self.root.borrow().clone()?;
+        self.unlink(&root);
+        Some(root.value())
+    }
+
+    // Append `value`, then evict and return the front value if the list has
+    // grown past `max_len`.
+    pub fn push_back_bounded(&self, value: T, max_len: usize) -> Option<Rc<T>> {
+        self.push_back(value);
+        if self.len() > max_len {
+            self.pop_front()
+        } else {
+            None
+        }
+    }
+
+    // Keep the values matching `f` and move the rest, in order, into a new
+    // list that is returned.
+    pub fn split_by<F: FnMut(&T) -> bool>(&self, mut f: F) -> DoublyLinkedList<'a, T> {
+        let (kept, removed): (Vec<_>, Vec<_>) =
+            self.nodes().into_iter().partition(|node| f(&node.value()));
+        self.relink(kept);
+        let rest = DoublyLinkedList::new();
+        rest.relink(removed);
+        rest
+    }
+
+    // Every overlapping group of `n` consecutive values, each as a new list.
+    pub fn each_cons(&self, n: usize) -> Vec<DoublyLinkedList<'a, T>>
+    where
+        T: Clone,
+    {
+        self.windows(n)
+            .iter()
+            .map(|window| window.iter().map(|value| (**value).clone()).collect())
+            .collect()
+    }
+
+    // Push each value to the front in iteration order, so the last one ends up
+    // first: prepending `[1, 2]` to `[3]` gives `[2, 1, 3]`. Use
+    // `push_front_many` to keep the values in their original order instead.
+    pub fn extend_front_reversed<I: IntoIterator<Item = T>>(&self, values: I) {
+        for value in values {
+            self.push_front(value);
+        }
+    }
+
+    // `(value, count)` pairs ordered by each value's first appearance.
+    pub fn value_counts(&self) -> Vec<(Rc<T>, usize)>
+    where
+        T: Eq + Hash + Clone,
+    {
+        let mut positions: HashMap<Rc<T>, usize> = HashMap::new();
+        let mut counts: Vec<(Rc<T>, usize)> = Vec::new();
+        for value in self.iter() {
+            match positions.get(&value) {
+                Some(&position) => counts[position].1 += 1,
+                None => {
+                    positions.insert(Rc::clone(&value), counts.len());
+                    counts.push((value, 1));
+                }
+            }
+        }
+        counts
+    }
+
+    // Consume the list into owned chunks of `size` values, unlinking each node
+    // as it is taken. Values still shared elsewhere are cloned.
+    pub fn into_chunks(self, size: usize) -> Vec<Vec<T>>
+    where
+        T: Clone,
+    {
+        assert!(size > 0, "chunk size must be non-zero");
+        let mut chunks: Vec<Vec<T>> = Vec::new();
+        while let Some(value) = self.pop_front() {
+            let value = Rc::try_unwrap(value).unwrap_or_else(|value| (*value).clone());
+            match chunks.last_mut() {
+                Some(chunk) if chunk.len() < size => chunk.push(value),
+                _ => chunks.push(vec![value]),
+            }
+        }
+        chunks
+    }
+
+    // Move every value matching `f` to the back, keeping the relative order
+    // within both the moved and the remaining values.
+    pub fn defer<F: FnMut(&T) -> bool>(&self, mut f: F) {
+        let (mut kept, deferred): (Vec<_>, Vec<_>) =
+            self.nodes().into_iter().partition(|node| !f(&node.value()));
+        kept.extend(deferred);
+        self.relink(kept);
+    }
+
+    // `Rc::strong_count` of each value, which is higher than one for values
+    // that are also held outside the list.
+    pub fn value_strong_counts(&self) -> Vec<usize> {
+        self.nodes()
+            .iter()
+            .map(|node| Rc::strong_count(&node.value.borrow()))
+            .collect()
+    }
+
+    // Append `other` unless it is empty, returning whether anything was added.
+    pub fn append_if_nonempty(&self, other: DoublyLinkedList<'a, T>) -> bool {
+        if other.is_empty() {
+            return false;
+        }
+        self.append(other);
+        true
+    }
+
+    // Reverse each complete group of `size` nodes, leaving a trailing partial
+    // group as it is. Returns the number of groups reversed.
+    pub fn reverse_chunks(&self, size: usize) -> usize {
+        assert!(size > 0, "chunk size must be non-zero");
+        let mut nodes = self.nodes();
+        let mut groups = 0;
+        for chunk in nodes.chunks_exact_mut(size) {
+            chunk.reverse();
+            groups += 1;
+        }
+        self.relink(nodes);
+        groups
+    }
+
+    pub fn to_index_map(&self) -> HashMap<usize, Rc<T>> {
+        self.iter().enumerate().collect()
+    }
+
+    // Merge another sorted list into this sorted one, dropping duplicates so
+    // the result is sorted and unique.
+    pub fn merge_dedup(&self, other: DoublyLinkedList<'a, T>)
+    where
+        T: Ord,
+    {
+        let ours = DoublyLinkedList::new();
+        ours.relink(self.nodes());
+        self.root.take();
+        let merged = merge_sorted(vec![ours, other]);
+        merged.dedup();
+        self.relink(merged.nodes());
+        merged.root.take();
+    }
+
+    // The index of and a handle to the first node whose value matches.
+    pub fn find_with_index<F: FnMut(&T) -> bool>(
+        &self,
+        mut predicate: F,
+    ) -> Option<(usize, NodeHandle<'a, T>)> {
+        self.iter_with_handles()
+            .enumerate()
+            .find(|(_, (_, value))| predicate(value))
+            .map(|(index, (handle, _))| (index, handle))
+    }
+
+    // A new list cloned from the values in `range`. Panics if the range is out
+    // of bounds.
+    pub fn clone_range(&self, range: Range<usize>) -> DoublyLinkedList<'a, T>
+    where
+        T: Clone,
+    {
+        self.values_between(range)
+            .into_iter()
+            .map(|value| (*value).clone())
+            .collect()
+    }
+
+    // Exchange the nodes of two ranges. Panics if the ranges overlap, differ
+    // in length or are out of bounds.
+    pub fn swap_ranges(&self, a: Range<usize>, b: Range<usize>) {
+        let mut nodes = self.nodes();
+        Self::check_range(&a, nodes.len());
+        Self::check_range(&b, nodes.len());
+        assert_eq!(a.len(), b.len(), "ranges must have the same length");
+        assert!(
+            a.end <= b.start || b.end <= a.start,
+            "ranges {:?} and {:?} overlap",
+            a,
+            b
+        );
+        for offset in 0..a.len() {
+            nodes.swap(a.start + offset, b.start + offset);
+        }
+        self.relink(nodes);
+    }
+
+    // The length of the longest run of strictly increasing consecutive values.
+    pub fn longest_increasing_run(&self) -> usize
+    where
+        T: PartialOrd,
+    {
+        let mut longest = 0;
+        let mut current = 0;
+        let mut last: Option<Rc<T>> = None;
+        for value in self.iter() {
+            current = match &last {
+                Some(last) if *value > **last => current + 1,
+                _ => 1,
+            };
+            longest = longest.max(current);
+            last = Some(value);
+        }
+        longest
+    }
+
+    // A new list of `f` applied to each sliding window of `size` values.
+    pub fn window_reduce<F: FnMut(&[Rc<T>]) -> T>(
+        &self,
+        size: usize,
+        mut f: F,
+    ) -> DoublyLinkedList<'a, T> {
+        let mut reduced = Vec::new();
+        self.for_each_window(size, |window| reduced.push(f(window)));
+        reduced.into_iter().collect()
+    }
+
+    // Render the links between the values, e.g. `1 <-> 2 <-> 3`, or `(empty)`.
+    pub fn to_arrow_string(&self) -> String
+    where
+        T: Display,
+    {
+        if self.is_empty() {
+            return "(empty)".to_string();
+        }
+        self.format("", "", " <-> ").to_string()
+    }
+
+    // Unlink the nodes in `range` into a new list without cloning, closing the
+    // gap they leave behind. Panics if the range is out of bounds.
+    pub fn detach_range(&self, range: Range<usize>) -> DoublyLinkedList<'a, T> {
+        let mut nodes = self.nodes();
+        Self::check_range(&range, nodes.len());
+        let detached = DoublyLinkedList::new();
+        detached.relink(nodes.drain(range).collect());
+        self.relink(nodes);
+        detached
+    }
+
+    // A rough estimate of the heap bytes held by the list: one `Rc` allocation
+    // per node plus one per value, each carrying strong and weak counts.
+    // Values shared with other lists are counted anyway.
+    pub fn estimated_heap_bytes(&self) -> usize {
+        let counts = 2 * std::mem::size_of::<usize>();
+        let per_node = std::mem::size_of::<Node<'a, T>>() + counts;
+        let per_value = std::mem::size_of::<T>() + counts;
+        self.len() * (per_node + per_value)
+    }
+}
+
+impl<'a, U> DoublyLinkedList<'a, Option<U>>
+where
+    U: Clone,
+{
+    // A new list of the `Some` values, unwrapped, in order.
+    pub fn flatten_options(&self) -> DoublyLinkedList<'a, U> {
+        self.iter().filter_map(|value| (*value).clone()).collect()
+    }
+}
+
+impl<'a, O, E> DoublyLinkedList<'a, Result<O, E>>
+where
+    O: Clone,
+    E: Clone,
+{
+    // Separate lists of the `Ok` and `Err` values, each in order.
+    pub fn split_results(&self) -> (DoublyLinkedList<'a, O>, DoublyLinkedList<'a, E>) {
+        let oks = self
+            .iter()
+            .filter_map(|value| (*value).as_ref().ok().cloned());
+        let errs = self
+            .iter()
+            .filter_map(|value| (*value).as_ref().err().cloned());
+        (oks.collect(), errs.collect())
+    }
+}
+
+// Expand `(value, count)` pairs into a list holding `count` clones of each
+// value, the inverse of `DoublyLinkedList::run_length_encode`.
+pub fn run_length_decode<'a, T: Clone>(pairs: &[(T, usize)]) -> DoublyLinkedList<'a, T> {
+    pairs
+        .iter()
+        .flat_map(|(value, count)| std::iter::repeat_n(value, *count))
+        .cloned()
+        .collect()
+}
+
+// Merge already sorted lists into one sorted list, reusing their nodes. The
+// next node is picked from a binary heap of the lists' current heads, with
+// ties going to the earlier list.
+pub fn merge_sorted<'a, T: Ord>(lists: Vec<DoublyLinkedList<'a, T>>) -> DoublyLinkedList<'a, T> {
+    let sources: Vec<Vec<Rc<Node<'a, T>>>> = lists.iter().map(|list| list.nodes()).collect();
+    for list in &lists {
+        list.root.take();
+    }
+    let mut positions = vec![0; sources.len()];
+    let mut heads: BinaryHeap<Reverse<(Rc<T>, usize)>> = sources
+        .iter()
+        .enumerate()
+        .filter_map(|(i, nodes)| Some(Reverse((nodes.first()?.value(), i))))
+        .collect();
+    let mut merged = Vec::with_capacity(sources.iter().map(Vec::len).sum());
+    while let Some(Reverse((_, i))) = heads.pop() {
+        merged.push(Rc::clone(&sources[i][positions[i]]));
+        positions[i] += 1;
+        if let Some(next) = sources[i].get(positions[i]) {
+            heads.push(Reverse((next.value(), i)));
+        }
+    }
+    let list = DoublyLinkedList::new();
+    list.relink(merged);
+    list
+}
+
+struct Node<'a, T> {
+    prev: RefCell<Option<Rc<Node<'a, T>>>>,
+    value: RefCell<Rc<T>>,
+    next: RefCell<Option<Rc<Node<'a, T>>>>,
+}
+
+impl<'a, T> std::fmt::Debug for Node<'a, T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        fmt.write_str(format!("{:?}", self.value.borrow()).as_ref())?;
+        if let Some(next) = self.next.take() {
+            fmt.write_str(",\n    ")?;
+            next.fmt(fmt)?;
+            *self.next.borrow_mut() = Some(next);
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T> std::fmt::Debug for DoublyLinkedList<'a, T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        fmt.write_str("DoublyLinkedList {\n")?;
+        let root = self.root.take();
+        if let Some(root) = root {
+            fmt.write_str("    ")?;
+            *self.root.borrow_mut() = Some(Rc::clone(&root));
+            root.fmt(fmt)?;
+            fmt.write_str("\n")?;
+        }
+        fmt.write_str("}")?;
+        Ok(())
+    }
+}
+
+impl<'a, T> Display for DoublyLinkedList<'a, T>
+where
+    T: Display,
+{
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        self.format("[", "]", ", ").fmt(fmt)
+    }
+}
+
+pub struct ListFormat<'a, 'b, T> {
+    list: &'b DoublyLinkedList<'a, T>,
+    open: &'static str,
+    close: &'static str,
+    sep: &'static str,
+}
+
+impl<'a, 'b, T> Display for ListFormat<'a, 'b, T>
+where
+    T: Display,
+{
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        fmt.write_str(self.open)?;
+        for (i, value) in self.list.iter().enumerate() {
+            if i > 0 {
+                fmt.write_str(self.sep)?;
+            }
+            value.fmt(fmt)?;
+        }
+        fmt.write_str(self.close)
+    }
+}
+
+impl<'a, T> Node<'a, T> {
+    pub fn last(root: Rc<Node<T>>) -> Rc<Node<T>> {
+        let mut node = root;
+        while let Some(next) = node.next.take() {
+            *node.next.borrow_mut() = Some(Rc::clone(&next));
+            node = next;
+        }
+        Rc::clone(&node)
+    }
+
+    fn new(value: Rc<T>) -> Rc<Node<'a, T>> {
+        Rc::new(Node {
+            prev: RefCell::new(None),
+            value: RefCell::new(value),
+            next: RefCell::new(None),
+        })
+    }
+
+    fn value(&self) -> Rc<T> {
+        self.value.borrow().clone()
+    }
+
+    fn next(&self) -> Option<Rc<Node<'a, T>>> {
+        self.next.borrow().clone()
+    }
+
+    fn prev(&self) -> Option<Rc<Node<'a, T>>> {
+        self.prev.borrow().clone()
+    }
+
+    // Drop both links so a node taken out of a list no longer keeps its
+    // former neighbours alive.
+    fn detach(&self) {
+        *self.prev.borrow_mut() = None;
+        *self.next.borrow_mut() = None;
+    }
+}
+
+// A reference to a single node of a list, used for O(1) operations on that
+// node. A handle keeps its node alive, but is only meaningful for the list the
+// node currently belongs to: passing a removed node, or a node of another
+// list, to `remove_node` or `move_to_front` panics when it can be detected and
+// is a logic error when it cannot.
+pub struct NodeHandle<'a, T> {
+    node: Rc<Node<'a, T>>,
+}
+
+impl<'a, T> NodeHandle<'a, T> {
+    pub fn value(&self) -> Rc<T> {
+        self.node.value()
+    }
+
+    // Whether both handles refer to the same node, regardless of its value.
+    pub fn same_node(&self, other: &NodeHandle<'a, T>) -> bool {
+        Rc::ptr_eq(&self.node, &other.node)
+    }
+}
+
+// Handles compare and hash by node identity, not by value.
+impl<'a, T> PartialEq for NodeHandle<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.same_node(other)
+    }
+}
+
+impl<'a, T> Eq for NodeHandle<'a, T> {}
+
+impl<'a, T> Hash for NodeHandle<'a, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.node).hash(state);
+    }
+}
+
+impl<'a, T> Clone for NodeHandle<'a, T> {
+    fn clone(&self) -> Self {
+        NodeHandle {
+            node: Rc::clone(&self.node),
+        }
+    }
+}
+
+// A read-only position in a list that can be moved in both directions.
+pub struct Cursor<'a, T> {
+    node: Option<Rc<Node<'a, T>>>,
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub fn current(&self) -> Option<Rc<T>> {
+        self.node.as_ref().map(|node| node.value())
+    }
+
+    // Step forward, staying put and returning false at the tail.
+    pub fn move_next(&mut self) -> bool {
+        match self.node.as_ref().and_then(|node| node.next()) {
+            Some(next) => {
+                self.node = Some(next);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Step backward, staying put and returning false at the head.
+    pub fn move_prev(&mut self) -> bool {
+        match self.node.as_ref().and_then(|node| node.prev()) {
+            Some(prev) => {
+                self.node = Some(prev);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn peek_next(&self) -> Option<Rc<T>> {
+        self.node.as_ref()?.next().map(|next| next.value())
+    }
+
+    pub fn peek_prev(&self) -> Option<Rc<T>> {
+        self.node.as_ref()?.prev().map(|prev| prev.value())
+    }
+}
+
+pub struct DrainBack<'b, 'a, T> {
+    list: &'b DoublyLinkedList<'a, T>,
+    tail: Option<Rc<Node<'a, T>>>,
+}
+
+impl<'b, 'a, T> Iterator for DrainBack<'b, 'a, T> {
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Rc<T>> {
+        let node = self.tail.take()?;
+        self.tail = node.prev();
+        self.list.unlink(&node);
+        Some(node.value())
+    }
+}
+
+impl<'b, 'a, T> Drop for DrainBack<'b, 'a, T> {
+    fn drop(&mut self) {
+        for _ in self.by_ref() {}
+    }
+}
+
+#[derive(Debug)]
+pub struct NodeIterator<'a, T> {
+    node: Option<Rc<Node<'a, T>>>,
+    reverse: bool,
+}
+
+// Get the next item in a node iterator.
+// `$key` should be either `prev` or `next`.
+macro_rules! iterate_in_direction {
+    ($self:ident, $key:ident) => {{
+        let node = $self.node.take();
+        match node {
+            Some(node) => {
+                let new_cell = node.value();
+                let value = node.$key.take();
+                $self.node = match value {
+                    Some(value) => {
+                        *node.$key.borrow_mut() = Some(Rc::clone(&value));
+                        Some(Rc::clone(&value))
+                    }
+                    None => None,
+                };
+                Some(new_cell)
+            }
+            None => None,
+        }
+    }};
+}
+
+impl<'a, T> Iterator for NodeIterator<'a, T> {
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Rc<T>> {
+        match self.reverse {
+            false => {
+                iterate_in_direction!(self, next)
+            }
+            true => {
+                iterate_in_direction!(self, prev)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values<T: Clone>(list: &DoublyLinkedList<T>) -> Vec<T> {
+        list.iter().map(|value| (*value).clone()).collect()
+    }
+
+    fn handles<'a, T>(list: &DoublyLinkedList<'a, T>) -> Vec<NodeHandle<'a, T>> {
+        list.iter_with_handles().map(|(handle, _)| handle).collect()
+    }
+
+    #[test]
+    fn it_can_debug_a_node() {
+        let node = Node {
+            prev: RefCell::new(None),
+            value: RefCell::new(Rc::new(1337)),
+            next: RefCell::new(None),
+        };
+
+        assert_eq!(format!("{:?}", node), "1337");
+    }
+
+    #[test]
+    fn it_can_debug_a_doubly_linked_node() {
+        let first = Rc::new(Node {
+            prev: RefCell::new(None),
+            value: RefCell::new(Rc::new("first")),
+            next: RefCell::new(None),
+        });
+        let second = Rc::new(Node {
+            prev: RefCell::new(Some(Rc::clone(&first))),
+            value: RefCell::new(Rc::new("second")),
+            next: RefCell::new(None),
+        });
+        *first.next.borrow_mut() = Some(Rc::clone(&second));
+
+        assert_eq!(format!("{:?}", first), "\"first\",\n    \"second\"");
+    }
+
+    #[test]
+    fn it_can_debug_a_linked_list() {
+        let list = doubly_linked_list!("first", "second", "third", "fourth");
+
+        assert_eq!(format!("{:?}", list), "DoublyLinkedList {\n    \"first\",\n    \"second\",\n    \"third\",\n    \"fourth\"\n}");
+    }
+
+    #[test]
+    fn it_can_iterate_over_the_items() {
+        let list = doubly_linked_list!(1, 2);
+
+        let mut out = Vec::<i32>::new();
+
+        for i in list.iter() {
+            out.push(*i.clone());
+        }
+
+        assert_eq!(out, vec![1, 2]);
+    }
+
+    #[test]
+    fn it_can_iterate_over_the_items_twice() {
+        let list = doubly_linked_list!(1, 2);
+
+        let mut first = Vec::<i32>::new();
+        let mut second = Vec::<i32>::new();
+
+        for i in list.iter() {
+            first.push(*i.clone());
+        }
+
+        for i in list.iter() {
+            second.push(*i.clone());
+        }
+
+        assert_eq!([first, second], [vec![1, 2], vec![1, 2]]);
+    }
+
+    #[test]
+    fn it_can_get_the_last_node() {
+        let list = doubly_linked_list!(1, 2);
+        let root = list.root.take().unwrap();
+        let last = Node::last(root);
+
+        assert_eq!(last.value(), Rc::new(2));
+    }
+
+    #[test]
+    fn it_can_reverse_iterate_over_the_items() {
+        let list = doubly_linked_list!(1, 2);
+
+        let mut out = Vec::<i32>::new();
+
+        for i in list.rev_iter() {
+            out.push(*i.clone());
+        }
+
+        assert_eq!(out, vec![2, 1]);
+    }
+
+    #[test]
+    fn it_can_reverse_iterate_over_the_items_multiple_times() {
+        let list = doubly_linked_list!(1, 2);
+
+        let mut first = Vec::<i32>::new();
+        let mut second = Vec::<i32>::new();
+
+        for i in list.rev_iter() {
+            first.push(*i.clone());
+        }
+
+        for i in list.rev_iter() {
+            second.push(*i.clone());
+        }
+
+        assert_eq!([first, second], [vec![2, 1], vec![2, 1]]);
+    }
+
+    #[test]
+    fn it_can_get_the_length() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert_eq!(list.len(), 3);
+        assert!(!list.is_empty());
+    }
+
+    #[test]
+    fn it_can_get_sliding_windows() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        let windows: Vec<Vec<i32>> = list
+            .windows(2)
+            .iter()
+            .map(|window| window.iter().map(|i| **i).collect())
+            .collect();
+
+        assert_eq!(windows, vec![vec![1, 2], vec![2, 3]]);
+    }
+
+    #[test]
+    fn it_can_sum_sliding_windows() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        assert_eq!(list.window_sums(2), vec![3, 5, 7]);
+        assert_eq!(list.window_sums(5), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn it_can_find_a_subsequence() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        assert_eq!(list.find_subsequence(&[2, 3]), Some(1));
+    }
+
+    #[test]
+    fn it_does_not_find_a_missing_subsequence() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        assert_eq!(list.find_subsequence(&[3, 2]), None);
+        assert_eq!(list.find_subsequence(&[1, 2, 3, 4, 5]), None);
+    }
+
+    #[test]
+    fn it_finds_an_empty_subsequence_at_the_start() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        assert_eq!(list.find_subsequence(&[]), Some(0));
+    }
+
+    #[test]
+    fn it_can_replace_a_subsequence() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        assert!(list.replace_subsequence(&[2, 3], &[9]));
+
+        let forward: Vec<i32> = list.iter().map(|i| *i).collect();
+        let backward: Vec<i32> = list.rev_iter().map(|i| *i).collect();
+        assert_eq!(forward, vec![1, 9, 4]);
+        assert_eq!(backward, vec![4, 9, 1]);
+    }
+
+    #[test]
+    fn it_does_not_replace_a_missing_subsequence() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        assert!(!list.replace_subsequence(&[5], &[9]));
+        assert_eq!(values(&list), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn it_can_get_the_median_of_an_odd_length_list() {
+        let list = doubly_linked_list!(5, 1, 3);
+
+        assert_eq!(list.median(), Some(Rc::new(3)));
+        assert_eq!(values(&list), vec![5, 1, 3]);
+    }
+
+    #[test]
+    fn it_can_get_the_lower_median_of_an_even_length_list() {
+        let list = doubly_linked_list!(4, 1, 3, 2);
+
+        assert_eq!(list.median(), Some(Rc::new(2)));
+    }
+
+    #[test]
+    fn it_has_no_median_when_empty() {
+        let list = DoublyLinkedList::<i32>::new();
+
+        assert_eq!(list.median(), None);
+    }
+
+    #[test]
+    fn it_can_count_value_frequencies() {
+        let list = doubly_linked_list!(1, 1, 2, 3, 3, 3);
+
+        assert_eq!(list.frequency(), HashMap::from([(1, 2), (2, 1), (3, 3)]));
+    }
+
+    #[test]
+    fn it_can_get_the_mode() {
+        let list = doubly_linked_list!(1, 2, 2, 3);
+
+        assert_eq!(list.mode(), Some(Rc::new(2)));
+    }
+
+    #[test]
+    fn it_resolves_mode_ties_to_the_first_value() {
+        let list = doubly_linked_list!(3, 1, 1, 3);
+
+        assert_eq!(list.mode(), Some(Rc::new(3)));
+        assert_eq!(DoublyLinkedList::<i32>::new().mode(), None);
+    }
+
+    #[test]
+    fn it_can_swap_adjacent_pairs() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        list.swap_pairs();
+
+        assert_eq!(values(&list), vec![2, 1, 4, 3, 5]);
+        assert_eq!(
+            list.rev_iter().map(|i| *i).collect::<Vec<i32>>(),
+            vec![5, 3, 4, 1, 2]
+        );
+    }
+
+    #[test]
+    fn it_considers_well_formed_lists_structurally_equal() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let other = doubly_linked_list!(1, 2, 3);
+
+        assert!(list.structurally_equal(&other));
+    }
+
+    #[test]
+    fn it_detects_broken_prev_links() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let other = doubly_linked_list!(1, 2, 3);
+        let nodes = other.nodes();
+        *nodes[2].prev.borrow_mut() = Some(Rc::clone(&nodes[0]));
+
+        assert_eq!(values(&list), values(&other));
+        assert!(!list.structurally_equal(&other));
+    }
+
+    #[test]
+    fn it_can_permute_the_nodes() {
+        let list = doubly_linked_list!(10, 20, 30);
+
+        list.permute(&[2, 0, 1]);
+
+        assert_eq!(values(&list), vec![30, 10, 20]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_permuting_with_repeated_indices() {
+        let list = doubly_linked_list!(10, 20, 30);
+
+        list.permute(&[0, 0, 1]);
+    }
+
+    #[test]
+    fn it_can_push_to_the_back() {
+        let list = DoublyLinkedList::new();
+
+        list.push_back(1);
+        list.push_back_many(vec![2, 3]);
+
+        assert_eq!(values(&list), vec![1, 2, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_collect_into_an_existing_list() {
+        let list = doubly_linked_list!(1, 2);
+
+        DoublyLinkedList::collect_into(&list, 3..6);
+
+        assert_eq!(values(&list), vec![1, 2, 3, 4, 5]);
+        assert_eq!(
+            list.rev_iter().map(|i| *i).collect::<Vec<i32>>(),
+            vec![5, 4, 3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn it_can_remove_the_last_match_through_a_handle() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        let handle = list.rfind_handle(|i| i % 2 == 0).unwrap();
+        assert_eq!(list.remove_node(&handle), Rc::new(4));
+
+        assert_eq!(values(&list), vec![1, 2, 3, 5]);
+        assert!(list.links_consistent());
+        assert!(list.rfind_handle(|i| *i > 5).is_none());
+    }
+
+    #[test]
+    fn it_can_remove_the_root_through_a_handle() {
+        let list = doubly_linked_list!(1, 2);
+
+        let handle = list.rfind_handle(|i| *i == 1).unwrap();
+        list.remove_node(&handle);
+
+        assert_eq!(values(&list), vec![2]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    #[should_panic(expected = "handle is not in this list")]
+    fn it_panics_when_removing_a_node_twice() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+        let handle = handles(&list)[1].clone();
+
+        list.remove_node(&handle);
+        list.remove_node(&handle);
+    }
+
+    #[test]
+    fn it_refuses_to_remove_the_head_of_another_list() {
+        let list = doubly_linked_list!(1, 2);
+        let other = doubly_linked_list!(10, 11, 12);
+        let head = other.front_handle().unwrap();
+
+        let removed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.remove_node(&head);
+        }));
+
+        assert!(removed.is_err());
+        assert_eq!(values(&list), vec![1, 2]);
+        assert_eq!(values(&other), vec![10, 11, 12]);
+        assert!(other.links_consistent());
+    }
+
+    #[test]
+    fn it_can_get_the_values_in_a_middle_range() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        assert_eq!(list.values_between(1..3), vec![Rc::new(2), Rc::new(3)]);
+        assert_eq!(list.values_between(2..2), vec![]);
+    }
+
+    #[test]
+    fn it_can_get_the_values_in_a_head_range() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        assert_eq!(list.values_between(0..2), vec![Rc::new(1), Rc::new(2)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_on_an_out_of_bounds_value_range() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        list.values_between(2..4);
+    }
+
+    #[test]
+    fn it_can_find_the_rotation_point() {
+        let list = doubly_linked_list!(3, 4, 5, 1, 2);
+
+        assert_eq!(list.rotation_point(), Some(3));
+    }
+
+    #[test]
+    fn it_has_no_rotation_point_when_sorted() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        assert_eq!(list.rotation_point(), None);
+        assert_eq!(doubly_linked_list!(3, 1, 4, 2).rotation_point(), None);
+    }
+
+    #[test]
+    fn it_can_normalize_a_rotation() {
+        let list = doubly_linked_list!(3, 4, 5, 1, 2);
+
+        list.normalize_rotation();
+
+        assert_eq!(values(&list), vec![1, 2, 3, 4, 5]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_leaves_unrotated_lists_alone_when_normalizing() {
+        let list = doubly_linked_list!(3, 1, 4, 2);
+
+        list.normalize_rotation();
+
+        assert_eq!(values(&list), vec![3, 1, 4, 2]);
+    }
+
+    #[test]
+    fn it_can_flatten_options() {
+        let list = doubly_linked_list!(Some(1), None, Some(3));
+
+        assert_eq!(values(&list.flatten_options()), vec![1, 3]);
+    }
+
+    #[test]
+    fn it_can_split_results() {
+        let list: DoublyLinkedList<Result<i32, &str>> =
+            doubly_linked_list!(Ok(1), Err("bad"), Ok(3), Err("worse"));
+
+        let (oks, errs) = list.split_results();
+
+        assert_eq!(values(&oks), vec![1, 3]);
+        assert_eq!(values(&errs), vec!["bad", "worse"]);
+    }
+
+    #[test]
+    fn it_iterates_consistently_in_both_directions() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert!(list.directions_consistent());
+    }
+
+    #[test]
+    fn it_detects_inconsistent_iteration_directions() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let nodes = list.nodes();
+        *nodes[2].prev.borrow_mut() = Some(Rc::clone(&nodes[0]));
+
+        assert!(!list.directions_consistent());
+    }
+
+    #[test]
+    fn it_can_append_another_list() {
+        let list = doubly_linked_list!(1, 2);
+
+        list.append(doubly_linked_list!(3, 4));
+
+        assert_eq!(values(&list), vec![1, 2, 3, 4]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_append_a_clone_of_another_list() {
+        let list = doubly_linked_list!(1, 2);
+        let other = doubly_linked_list!(3, 4);
+
+        list.append_cloned(&other);
+
+        assert_eq!(values(&list), vec![1, 2, 3, 4]);
+        assert!(list.links_consistent());
+        assert_eq!(values(&other), vec![3, 4]);
+        assert!(other.links_consistent());
+    }
+
+    #[test]
+    fn it_can_append_a_clone_of_itself() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        list.append_cloned(&list);
+
+        assert_eq!(values(&list), vec![1, 2, 3, 1, 2, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_iterate_with_handles() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        for (handle, value) in list.iter_with_handles() {
+            if *value % 2 == 0 {
+                list.remove_node(&handle);
+            }
+        }
+
+        assert_eq!(values(&list), vec![1, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_remove_the_last_match_through_reverse_handles() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        let (handle, _) = list
+            .iter_handles_rev()
+            .find(|(_, value)| **value % 2 == 0)
+            .unwrap();
+        list.remove_node(&handle);
+
+        assert_eq!(values(&list), vec![1, 2, 3, 5]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_compact_a_healthy_list() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert_eq!(list.compact(), Ok(()));
+    }
+
+    #[test]
+    fn it_reports_corruption_when_compacting() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let nodes = list.nodes();
+        *nodes[2].prev.borrow_mut() = None;
+
+        assert_eq!(
+            list.compact(),
+            Err("node 2 is missing its prev link".to_string())
+        );
+        assert_eq!(values(&list), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn it_can_insert_many_values_at_an_index() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        list.insert_many(1, vec![8, 9]);
+
+        assert_eq!(values(&list), vec![1, 8, 9, 2, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_insert_many_values_at_the_ends() {
+        let list = doubly_linked_list!(1, 2);
+
+        list.insert_many(0, vec![0]);
+        list.insert_many(3, vec![3, 4]);
+
+        assert_eq!(values(&list), vec![0, 1, 2, 3, 4]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_inserting_many_out_of_bounds() {
+        let list = doubly_linked_list!(1, 2);
+
+        list.insert_many(3, vec![3]);
+    }
+
+    #[test]
+    fn it_can_get_the_first_n_values() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        assert_eq!(list.first_n(2), vec![Rc::new(1), Rc::new(2)]);
+        assert_eq!(list.first_n(9).len(), 5);
+    }
+
+    #[test]
+    fn it_can_get_the_last_n_values() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        assert_eq!(list.last_n(2), vec![Rc::new(4), Rc::new(5)]);
+        assert_eq!(list.last_n(9).len(), 5);
+    }
+
+    #[test]
+    fn it_can_reverse_between_handles() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+        let handles = handles(&list);
+
+        list.reverse_between(&handles[1], &handles[3]);
+
+        assert_eq!(values(&list), vec![1, 4, 3, 2, 5]);
+        assert_eq!(list.validate(), Ok(()));
+    }
+
+    #[test]
+    fn it_can_reverse_between_handles_from_the_head() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let handles = handles(&list);
+
+        list.reverse_between(&handles[0], &handles[1]);
+
+        assert_eq!(values(&list), vec![2, 1, 3]);
+        assert_eq!(list.validate(), Ok(()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_reversing_between_handles_out_of_order() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let handles = handles(&list);
+
+        list.reverse_between(&handles[2], &handles[0]);
+    }
+
+    #[test]
+    fn it_can_get_a_value_relative_to_a_handle() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+        let middle = &handles(&list)[2];
+
+        assert_eq!(list.value_at_offset(middle, 2), Some(Rc::new(5)));
+        assert_eq!(list.value_at_offset(middle, -1), Some(Rc::new(2)));
+        assert_eq!(list.value_at_offset(middle, 0), Some(Rc::new(3)));
+        assert_eq!(list.value_at_offset(middle, 3), None);
+        assert_eq!(list.value_at_offset(middle, -3), None);
+    }
+
+    #[test]
+    fn it_can_trim_both_ends() {
+        let list = doubly_linked_list!(0, 0, 1, 0, 2, 0, 0);
+
+        list.trim(|i| *i == 0);
+
+        assert_eq!(values(&list), vec![1, 0, 2]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_trim_everything() {
+        let list = doubly_linked_list!(0, 0);
+
+        list.trim(|i| *i == 0);
+
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn it_can_trim_the_front() {
+        let list = doubly_linked_list!(0, 0, 1, 0, 2, 0, 0);
+
+        list.trim_front(|i| *i == 0);
+
+        assert_eq!(values(&list), vec![1, 0, 2, 0, 0]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_trim_the_back() {
+        let list = doubly_linked_list!(0, 0, 1, 0, 2, 0, 0);
+
+        list.trim_back(|i| *i == 0);
+
+        assert_eq!(values(&list), vec![0, 0, 1, 0, 2]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_visit_each_window() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        let mut sums = Vec::new();
+        list.for_each_window(2, |window| {
+            sums.push(window.iter().map(|i| **i).sum::<i32>())
+        });
+
+        assert_eq!(sums, list.window_sums(2));
+    }
+
+    #[test]
+    fn it_can_compare_with_a_matching_slice() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert!(list.eq_slice(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn it_does_not_equal_a_slice_of_another_length() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert!(!list.eq_slice(&[1, 2]));
+        assert!(!list.eq_slice(&[1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn it_does_not_equal_a_slice_with_other_elements() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert!(!list.eq_slice(&[1, 5, 3]));
+    }
+
+    #[test]
+    fn it_can_iterate_outward_from_a_handle() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+        let middle = &handles(&list)[2];
+
+        let out: Vec<i32> = list.iter_from_both(middle).map(|i| *i).collect();
+
+        assert_eq!(out, vec![3, 4, 2, 5, 1]);
+    }
+
+    #[test]
+    fn it_keeps_iterating_outward_once_one_side_runs_out() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+        let second = &handles(&list)[1];
+
+        let out: Vec<i32> = list.iter_from_both(second).map(|i| *i).collect();
+
+        assert_eq!(out, vec![2, 3, 1, 4, 5]);
+    }
+
+    #[test]
+    fn it_can_be_collected_from_an_exact_size_iterator() {
+        let source = vec![1, 2, 3, 4];
+
+        let list: DoublyLinkedList<i32> = source.into_iter().collect();
+
+        assert_eq!(list.len(), 4);
+        assert_eq!(values(&list), vec![1, 2, 3, 4]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_drain_from_the_back() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        let drained: Vec<i32> = list.drain_back().map(|i| *i).collect();
+
+        assert_eq!(drained, vec![3, 2, 1]);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn it_finishes_draining_from_the_back_when_dropped_early() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        let last = list.drain_back().next();
+
+        assert_eq!(last, Some(Rc::new(3)));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn it_can_swap_the_value_of_a_node() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let middle = &handles(&list)[1];
+
+        let old = list.swap_value(middle, 20);
+
+        assert_eq!(old, Rc::new(2));
+        assert_eq!(values(&list), vec![1, 20, 3]);
+        assert_eq!(middle.value(), Rc::new(20));
+    }
+
+    #[derive(PartialEq)]
+    struct Opaque(i32);
+
+    #[test]
+    fn it_contains_a_present_subsequence() {
+        let list = doubly_linked_list!(Opaque(1), Opaque(2), Opaque(3));
+
+        assert!(list.contains_subsequence(&[Opaque(2), Opaque(3)]));
+    }
+
+    #[test]
+    fn it_does_not_contain_an_absent_subsequence() {
+        let list = doubly_linked_list!(Opaque(1), Opaque(2), Opaque(3));
+
+        assert!(!list.contains_subsequence(&[Opaque(1), Opaque(3)]));
+    }
+
+    #[test]
+    fn it_contains_the_empty_subsequence() {
+        let list = doubly_linked_list!(Opaque(1), Opaque(2), Opaque(3));
+
+        assert!(list.contains_subsequence(&[]));
+    }
+
+    #[test]
+    fn it_can_remove_by_index() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert_eq!(list.remove(1), Rc::new(2));
+
+        assert_eq!(values(&list), vec![1, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_removing_out_of_bounds() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        list.remove(3);
+    }
+
+    #[test]
+    fn it_can_remove_by_index_while_iterating_backwards() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5, 6);
+
+        for (index, value) in list.rev_iter_indexed() {
+            if *value % 2 == 0 {
+                list.remove(index);
+            }
+        }
+
+        assert_eq!(values(&list), vec![1, 3, 5]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_share_a_value() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        let first = list.share(1).unwrap();
+        assert_eq!(Rc::strong_count(&first), 2);
+        let second = list.share(1).unwrap();
+
+        assert_eq!(Rc::strong_count(&first), 3);
+        assert!(Rc::ptr_eq(&first, &second));
+        assert!(list.share(3).is_none());
+    }
+
+    #[test]
+    fn it_can_keep_the_heaviest_value_per_run() {
+        let list = doubly_linked_list!(
+            ("a", "z", 1),
+            ("a", "b", 3),
+            ("a", "c", 2),
+            ("b", "a", 5),
+            ("a", "y", 4),
+            ("a", "x", 4)
+        );
+
+        list.dedup_keep_max_by_key(|(label, _, _)| *label, |(_, _, weight)| *weight);
+
+        assert_eq!(
+            values(&list),
+            vec![("a", "b", 3), ("b", "a", 5), ("a", "y", 4)]
+        );
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_convert_to_a_hash_set() {
+        let list = doubly_linked_list!(3, 1, 3, 2, 1);
+
+        assert_eq!(list.to_hash_set(), HashSet::from([1, 2, 3]));
+    }
+
+    #[test]
+    fn it_can_convert_to_a_sorted_btree_set() {
+        let list = doubly_linked_list!(3, 1, 3, 2, 1);
+
+        let set = list.to_btree_set();
+
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.into_iter().collect::<Vec<i32>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn it_can_get_the_symmetric_difference() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let other = doubly_linked_list!(2, 3, 4);
+
+        assert_eq!(values(&list.symmetric_difference(&other)), vec![1, 4]);
+    }
+
+    #[test]
+    fn it_lists_each_symmetric_difference_value_once() {
+        let list = doubly_linked_list!(5, 1, 5);
+        let other = doubly_linked_list!(4, 1, 4);
+
+        assert_eq!(values(&list.symmetric_difference(&other)), vec![5, 4]);
+    }
+
+    #[test]
+    fn it_can_get_the_intersection() {
+        let list = doubly_linked_list!(1, 2, 2, 3);
+        let other = doubly_linked_list!(2, 3, 4);
+
+        assert_eq!(values(&list.intersection(&other)), vec![2, 3]);
+    }
+
+    #[test]
+    fn it_can_get_the_difference() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let other = doubly_linked_list!(2);
+
+        assert_eq!(values(&list.difference(&other)), vec![1, 3]);
+    }
+
+    #[test]
+    fn it_can_create_a_single_element_list() {
+        let list = doubly_linked_list!(1);
+
+        assert_eq!(values(&list), vec![1]);
+    }
+
+    #[test]
+    fn it_can_run_length_encode() {
+        let list = doubly_linked_list!(1, 1, 2, 3, 3, 3);
+
+        assert_eq!(
+            list.run_length_encode(),
+            vec![(Rc::new(1), 2), (Rc::new(2), 1), (Rc::new(3), 3)]
+        );
+    }
+
+    #[test]
+    fn it_can_run_length_decode() {
+        let list = run_length_decode(&[(1, 2), (3, 0), (2, 1)]);
+
+        assert_eq!(values(&list), vec![1, 1, 2]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_iterate_without_consecutive_duplicates() {
+        let list = doubly_linked_list!(1, 1, 2, 2, 3, 1);
+
+        let out: Vec<i32> = list.iter_dedup().map(|i| *i).collect();
+
+        assert_eq!(out, vec![1, 2, 3, 1]);
+        assert_eq!(list.len(), 6);
+    }
+
+    #[test]
+    fn it_can_get_prefix_sums() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert_eq!(values(&list.prefix_sums()), vec![1, 3, 6]);
+        assert!(DoublyLinkedList::<i32>::new().prefix_sums().is_empty());
+    }
+
+    #[test]
+    fn it_can_get_successive_differences() {
+        let list = doubly_linked_list!(1, 3, 6);
+
+        assert_eq!(values(&list.differences()), vec![2, 3]);
+        assert!(doubly_linked_list!(1).differences().is_empty());
+    }
+
+    #[test]
+    fn it_can_rotate_left() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        list.rotate_left(2);
+
+        assert_eq!(values(&list), vec![3, 4, 5, 1, 2]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_rotate_right() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        list.rotate_right(7);
+
+        assert_eq!(values(&list), vec![4, 5, 1, 2, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_rotates_the_same_either_way_around() {
+        let left = doubly_linked_list!(1, 2, 3, 4, 5);
+        let right = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        left.rotate_left(2);
+        right.rotate_right(right.len() - 2);
+
+        assert!(left.structurally_equal(&right));
+    }
+
+    #[test]
+    fn it_can_rotate_by_huge_amounts() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        list.rotate_left(usize::MAX);
+        assert_eq!(values(&list), vec![1, 2, 3]);
+
+        list.rotate_right(usize::MAX - 1);
+        assert_eq!(values(&list), vec![2, 3, 1]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_move_a_cursor() {
+        let list = doubly_linked_list!(1, 2);
+        let mut cursor = list.cursor();
+
+        assert_eq!(cursor.current(), Some(Rc::new(1)));
+        assert!(!cursor.move_prev());
+        assert!(cursor.move_next());
+        assert_eq!(cursor.current(), Some(Rc::new(2)));
+        assert!(!cursor.move_next());
+        assert_eq!(cursor.current(), Some(Rc::new(2)));
+    }
+
+    #[test]
+    fn it_can_peek_around_a_cursor() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let mut cursor = list.cursor();
+        cursor.move_next();
+
+        assert_eq!(cursor.peek_next(), Some(Rc::new(3)));
+        assert_eq!(cursor.peek_prev(), Some(Rc::new(1)));
+        assert_eq!(cursor.current(), Some(Rc::new(2)));
+    }
+
+    #[test]
+    fn it_has_nothing_to_peek_at_in_an_empty_list() {
+        let list = DoublyLinkedList::<i32>::new();
+        let cursor = list.cursor();
+
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), None);
+        assert_eq!(cursor.peek_prev(), None);
+    }
+
+    #[test]
+    fn it_can_take_both_snapshots_at_once() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        let (forward, mut backward) = list.snapshots();
+
+        assert_eq!(backward, list.rev_iter().collect::<Vec<Rc<i32>>>());
+        backward.reverse();
+        assert_eq!(forward, backward);
+    }
+
+    #[test]
+    fn it_can_convert_to_an_arena() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        let arena = list.to_arena();
+
+        assert_eq!(arena.len(), list.len());
+        assert_eq!(arena.iter().copied().collect::<Vec<i32>>(), values(&list));
+    }
+
+    #[test]
+    fn it_can_round_trip_through_an_arena() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        let round_tripped = DoublyLinkedList::from_arena(&list.to_arena());
+
+        assert!(round_tripped.structurally_equal(&list));
+    }
+
+    #[test]
+    fn it_frees_its_nodes_when_dropped() {
+        let tracker = Rc::new(());
+        let list = doubly_linked_list!(Rc::clone(&tracker), Rc::clone(&tracker));
+        assert_eq!(Rc::strong_count(&tracker), 3);
+
+        drop(list);
+
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn it_can_be_built_from_ok_results() {
+        let list = DoublyLinkedList::try_from_iter(vec![Ok::<i32, &str>(1), Ok(2), Ok(3)]);
+
+        assert_eq!(values(&list.unwrap()), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn it_stops_building_at_the_first_error() {
+        let tracker = Rc::new(());
+        let items = vec![
+            Ok(Rc::clone(&tracker)),
+            Ok(Rc::clone(&tracker)),
+            Err("third"),
+            Ok(Rc::clone(&tracker)),
+        ];
+
+        let list = DoublyLinkedList::try_from_iter(items);
+
+        assert_eq!(list.err(), Some("third"));
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn it_can_inspect_each_value() {
+        let list = doubly_linked_list!(Opaque(1), Opaque(2), Opaque(3));
+
+        let mut calls = 0;
+        let total = list.inspect_each(|_| calls += 1).len();
+
+        assert_eq!(calls, 3);
+        assert_eq!(total, 3);
+        assert!(list.eq_slice(&[Opaque(1), Opaque(2), Opaque(3)]));
+    }
+
+    #[test]
+    fn it_can_sort() {
+        let list = doubly_linked_list!(3, 1, 2, 1, 3);
+
+        list.sort();
+
+        assert_eq!(values(&list), vec![1, 1, 2, 3, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_remove_consecutive_duplicates() {
+        let list = doubly_linked_list!(1, 1, 2, 1, 1);
+
+        list.dedup();
+
+        assert_eq!(values(&list), vec![1, 2, 1]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_sort_and_dedup() {
+        let list = doubly_linked_list!(3, 1, 2, 1, 3);
+
+        list.sort_dedup();
+
+        assert_eq!(values(&list), vec![1, 2, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_merge_many_sorted_lists() {
+        let lists = vec![
+            doubly_linked_list!(1, 4),
+            doubly_linked_list!(2, 5),
+            DoublyLinkedList::new(),
+            doubly_linked_list!(3, 6),
+        ];
+
+        let merged = merge_sorted(lists);
+
+        assert_eq!(values(&merged), vec![1, 2, 3, 4, 5, 6]);
+        assert!(merged.links_consistent());
+    }
+
+    #[test]
+    fn it_can_take_back_uniquely_owned_values() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let shared = list.share(1).unwrap();
+
+        let results = list.into_value_results();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], Ok(1));
+        assert!(matches!(&results[1], Err(rc) if Rc::ptr_eq(rc, &shared)));
+        assert_eq!(results[2], Ok(3));
+    }
+
+    #[test]
+    fn it_cannot_take_back_values_whose_node_has_a_live_handle() {
+        let list = doubly_linked_list!(1, 2);
+        let handle = list.front_handle().unwrap();
+
+        let results = list.into_value_results();
+
+        assert!(matches!(&results[0], Err(rc) if *rc == handle.value()));
+        assert_eq!(results[1], Ok(2));
+    }
+
+    #[test]
+    fn it_can_rotate_to_the_first_max() {
+        let list = doubly_linked_list!(1, 3, 2, 3);
+
+        list.rotate_to_max();
+
+        assert_eq!(values(&list), vec![3, 2, 3, 1]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_find_the_insertion_index() {
+        let list = doubly_linked_list!(1, 3, 5);
+
+        assert_eq!(list.insertion_index(&4), 2);
+        assert_eq!(list.insertion_index(&3), 2);
+        assert_eq!(list.insertion_index(&0), 0);
+        assert_eq!(list.insertion_index(&9), 3);
+    }
+
+    #[test]
+    fn it_can_group_runs_into_sublists() {
+        let list = doubly_linked_list!(1, 1, 2, 1, 1, 1);
+
+        let runs: Vec<Vec<i32>> = list.runs().iter().map(values).collect();
+
+        assert_eq!(runs, vec![vec![1, 1], vec![2], vec![1, 1, 1]]);
+    }
+
+    #[test]
+    fn it_can_mutate_values_while_collecting_handles() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        let mut odd = Vec::new();
+        list.for_each_mut_with_handles(|handle, value| {
+            *value *= 10;
+            if *value % 20 != 0 {
+                odd.push(handle.clone());
+            }
+        });
+        for handle in &odd {
+            list.remove_node(handle);
+        }
+
+        assert_eq!(values(&list), vec![20, 40]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_clones_shared_values_before_mutating_them() {
+        let list = doubly_linked_list!(1, 2);
+        let shared = list.share(0).unwrap();
+
+        list.for_each_mut_with_handles(|_, value| *value += 1);
+
+        assert_eq!(values(&list), vec![2, 3]);
+        assert_eq!(shared, Rc::new(1));
+    }
+
+    #[test]
+    fn it_can_read_the_list_while_mutating_with_handles() {
+        let list = doubly_linked_list!(1, 2);
+
+        list.for_each_mut_with_handles(|handle, value| {
+            *value += *handle.value() + list.len() as i32;
+        });
+
+        assert_eq!(values(&list), vec![4, 6]);
+    }
+
+    #[test]
+    fn it_can_be_consumed_into_a_reversed_vec() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert_eq!(list.into_reversed_vec(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn it_frees_its_nodes_when_consumed_into_a_reversed_vec() {
+        let tracker = Rc::new(());
+        let list = doubly_linked_list!(Rc::clone(&tracker), Rc::clone(&tracker));
+
+        let reversed = list.into_reversed_vec();
+        assert_eq!(Rc::strong_count(&tracker), 3);
+        drop(reversed);
+
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn it_can_display_a_linked_list() {
+        assert_eq!(doubly_linked_list!(1, 2).to_string(), "[1, 2]");
+        assert_eq!(DoublyLinkedList::<i32>::new().to_string(), "[]");
+    }
+
+    #[test]
+    fn it_can_display_with_custom_delimiters() {
+        let list = doubly_linked_list!(1, 2);
+
+        assert_eq!(list.format("(", ")", "; ").to_string(), "(1; 2)");
+    }
+
+    #[test]
+    fn it_compares_handles_by_node_identity() {
+        let list = doubly_linked_list!(7, 7);
+        let handles = handles(&list);
+        let first_again = list.iter_with_handles().next().unwrap().0;
+
+        assert!(handles[0].same_node(&first_again));
+        assert!(handles[0] == first_again);
+        assert!(!handles[0].same_node(&handles[1]));
+        assert!(handles[0] != handles[1]);
+        assert_eq!(HashSet::from([handles[0].clone(), first_again]).len(), 1);
+    }
+
+    #[test]
+    fn it_can_rotate_a_range() {
+        let list = doubly_linked_list!(0, 1, 2, 3, 4);
+
+        list.rotate_range(1..4, 1);
+
+        assert_eq!(values(&list), vec![0, 2, 3, 1, 4]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_wraps_around_when_rotating_a_range() {
+        let list = doubly_linked_list!(0, 1, 2, 3, 4);
+
+        list.rotate_range(0..3, 5);
+
+        assert_eq!(values(&list), vec![2, 0, 1, 3, 4]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_rotating_an_out_of_bounds_range() {
+        let list = doubly_linked_list!(0, 1, 2);
+
+        list.rotate_range(1..4, 1);
+    }
+
+    #[test]
+    fn it_can_squash_overlapping_intervals() {
+        let list = doubly_linked_list!((1, 3), (2, 5), (4, 6), (7, 8), (8, 10), (12, 13));
+
+        list.squash(|a, b| {
+            if b.0 <= a.1 {
+                Some((a.0, a.1.max(b.1)))
+            } else {
+                None
+            }
+        });
+
+        assert_eq!(values(&list), vec![(1, 6), (7, 10), (12, 13)]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_insert_at_an_index() {
+        let list = doubly_linked_list!(1, 3);
+
+        list.insert(1, 2);
+
+        assert_eq!(values(&list), vec![1, 2, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_try_to_insert_in_range() {
+        let list = doubly_linked_list!(1, 2);
+
+        assert_eq!(list.try_insert(2, 3), Ok(()));
+        assert_eq!(values(&list), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn it_hands_back_the_value_when_inserting_out_of_range() {
+        let list = doubly_linked_list!(1, 2);
+
+        assert_eq!(list.try_insert(3, 9), Err(9));
+        assert_eq!(values(&list), vec![1, 2]);
+    }
+
+    #[test]
+    fn it_can_try_to_remove_in_range() {
+        let list = doubly_linked_list!(1, 2);
+
+        assert_eq!(list.try_remove(0), Some(Rc::new(1)));
+        assert_eq!(values(&list), vec![2]);
+    }
+
+    #[test]
+    fn it_returns_none_when_removing_out_of_range() {
+        let list = doubly_linked_list!(1, 2);
+
+        assert_eq!(list.try_remove(2), None);
+        assert_eq!(values(&list), vec![1, 2]);
+    }
+
+    #[test]
+    fn it_can_remove_several_indices() {
+        let list = doubly_linked_list!(10, 20, 30, 40);
+
+        let removed = list.remove_indices(&[3, 0, 2, 3]);
+
+        assert_eq!(removed, vec![Rc::new(10), Rc::new(30), Rc::new(40)]);
+        assert_eq!(values(&list), vec![20]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_removing_an_out_of_bounds_index() {
+        let list = doubly_linked_list!(10, 20);
+
+        list.remove_indices(&[0, 2]);
+    }
+
+    #[test]
+    fn it_can_retain_several_indices() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        list.retain_indices(&[2, 0]);
+
+        assert_eq!(values(&list), vec![1, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_retaining_an_out_of_bounds_index() {
+        let list = doubly_linked_list!(1, 2);
+
+        list.retain_indices(&[5]);
+    }
+
+    #[test]
+    fn it_can_push_to_the_front() {
+        let list = doubly_linked_list!(2, 3);
+
+        list.push_front(1);
+
+        assert_eq!(values(&list), vec![1, 2, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_pop_from_the_back() {
+        let list = doubly_linked_list!(1, 2);
+
+        assert_eq!(list.pop_back(), Some(Rc::new(2)));
+        assert_eq!(list.pop_back(), Some(Rc::new(1)));
+        assert_eq!(list.pop_back(), None);
+    }
+
+    #[test]
+    fn it_can_move_a_node_to_the_front() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let handles = handles(&list);
+
+        list.move_to_front(&handles[2]);
+        list.move_to_front(&handles[2]);
+
+        assert_eq!(values(&list), vec![3, 1, 2]);
+        assert!(list.links_consistent());
+        assert!(list.front_handle().unwrap().same_node(&handles[2]));
+    }
+
+    #[test]
+    #[should_panic(expected = "handle is not in this list")]
+    fn it_panics_when_moving_a_removed_node_to_the_front() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let handle = handles(&list)[1].clone();
+
+        list.remove_node(&handle);
+        list.move_to_front(&handle);
+    }
+
+    #[test]
+    #[should_panic(expected = "handle is not in this list")]
+    fn it_panics_when_moving_the_head_of_another_list_to_the_front() {
+        let list = doubly_linked_list!(1, 2);
+        let other = doubly_linked_list!(10, 11);
+
+        list.move_to_front(&other.front_handle().unwrap());
+    }
+
+    #[test]
+    fn it_can_sort_with_a_comparator() {
+        let list = doubly_linked_list!(1, 3, 2);
+
+        list.sort_by(|a, b| b.cmp(a));
+
+        assert_eq!(values(&list), vec![3, 2, 1]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_stably_sort_by_key() {
+        let list = doubly_linked_list!(("ann", 30), ("bob", 25), ("cat", 30), ("dan", 25));
+
+        list.sort_by_key(|(_, age)| *age);
+
+        assert_eq!(
+            values(&list),
+            vec![("bob", 25), ("dan", 25), ("ann", 30), ("cat", 30)]
+        );
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_set_the_head_to_a_handle() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+        let handles = handles(&list);
+
+        list.set_head(&handles[2]);
+
+        assert_eq!(values(&list), vec![3, 4, 1, 2]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_setting_the_head_to_a_foreign_handle() {
+        let list = doubly_linked_list!(1, 2);
+        let other = doubly_linked_list!(1, 2);
+
+        list.set_head(&handles(&other)[0]);
+    }
+
+    #[test]
+    fn it_can_iterate_in_fixed_size_batches() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        let batches: Vec<[Rc<i32>; 2]> = list.iter_batched::<2>().collect();
+
+        assert_eq!(
+            batches,
+            vec![[Rc::new(1), Rc::new(2)], [Rc::new(3), Rc::new(4)]]
+        );
+    }
+
+    #[test]
+    fn it_can_push_many_to_the_front() {
+        let list = doubly_linked_list!(3, 4);
+
+        list.push_front_many(vec![1, 2]);
+
+        assert_eq!(values(&list), vec![1, 2, 3, 4]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_is_consistent_with_its_values() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert_eq!(list.assert_consistent_with(&[1, 2, 3]), Ok(()));
+    }
+
+    #[test]
+    fn it_reports_a_forward_mismatch() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert_eq!(
+            list.assert_consistent_with(&[1, 5, 3]),
+            Err("forward index 1: expected Some(5), found Some(2)".to_string())
+        );
+        assert_eq!(
+            list.assert_consistent_with(&[1, 2]),
+            Err("forward index 2: expected None, found Some(3)".to_string())
+        );
+    }
+
+    #[test]
+    fn it_reports_a_corrupted_reverse_order() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let nodes = list.nodes();
+        *nodes[2].prev.borrow_mut() = Some(Rc::clone(&nodes[0]));
+
+        assert_eq!(
+            list.assert_consistent_with(&[1, 2, 3]),
+            Err("reverse index 1: expected Some(2), found Some(1)".to_string())
+        );
+    }
+
+    #[test]
+    fn it_can_map_values_in_place() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let before = handles(&list);
+
+        list.map_in_place(|i| -i);
+
+        assert_eq!(values(&list), vec![-1, -2, -3]);
+        assert!(before.iter().eq(handles(&list).iter()));
+    }
+
+    #[test]
+    fn it_can_read_the_list_while_mapping_in_place() {
+        let list = doubly_linked_list!(1, 2);
+
+        list.map_in_place(|i| i + list.len() as i32);
+
+        assert_eq!(values(&list), vec![3, 4]);
+    }
+
+    #[test]
+    fn it_can_measure_a_common_prefix() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert_eq!(list.common_prefix_len(&doubly_linked_list!(1, 2, 9)), 2);
+        assert_eq!(list.common_prefix_len(&doubly_linked_list!(1, 2)), 2);
+    }
+
+    #[test]
+    fn it_has_no_common_prefix_with_a_disjoint_list() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        assert_eq!(list.common_prefix_len(&doubly_linked_list!(4, 5, 6)), 0);
+    }
+
+    #[test]
+    fn it_can_zip_with_a_closure() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let other = doubly_linked_list!(10, 20);
+
+        assert_eq!(values(&list.zip_with(&other, |a, b| a + b)), vec![11, 22]);
+    }
+
+    #[test]
+    fn it_can_split_at_a_value() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        let (before, after) = list.split_at_value(&3).unwrap();
+
+        assert_eq!(values(&before), vec![1, 2]);
+        assert_eq!(values(&after), vec![3, 4]);
+        assert!(list.split_at_value(&5).is_none());
+    }
+
+    #[test]
+    fn it_can_count_runs() {
+        assert_eq!(doubly_linked_list!(1, 1, 2, 1).count_runs(), 3);
+        assert_eq!(DoublyLinkedList::<i32>::new().count_runs(), 0);
+    }
+
+    #[test]
+    fn it_detects_palindromes() {
+        assert!(doubly_linked_list!(1, 2, 1).is_palindrome());
+        assert!(doubly_linked_list!(1, 2, 2, 1).is_palindrome());
+        assert!(DoublyLinkedList::<i32>::new().is_palindrome());
+    }
+
+    #[test]
+    fn it_detects_non_palindromes() {
+        assert!(!doubly_linked_list!(1, 2, 3).is_palindrome());
+        assert!(!doubly_linked_list!(1, 2, 3, 1).is_palindrome());
+    }
+
+    #[test]
+    fn it_considers_a_single_element_a_palindrome() {
+        assert!(doubly_linked_list!(1).is_palindrome());
+    }
+
+    #[test]
+    fn it_can_get_the_smallest_rotation() {
+        assert_eq!(
+            values(&doubly_linked_list!(3, 1, 2).min_rotation()),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            values(&doubly_linked_list!(2, 1, 2).min_rotation()),
+            vec![1, 2, 2]
+        );
+        assert!(DoublyLinkedList::<i32>::new().min_rotation().is_empty());
+    }
+
+    #[test]
+    fn it_can_pop_from_the_front() {
+        let list = doubly_linked_list!(1, 2);
+
+        assert_eq!(list.pop_front(), Some(Rc::new(1)));
+        assert_eq!(list.pop_front(), Some(Rc::new(2)));
+        assert_eq!(list.pop_front(), None);
+    }
+
+    #[test]
+    fn it_evicts_the_oldest_value_when_bounded() {
+        let list = DoublyLinkedList::new();
+
+        assert_eq!(list.push_back_bounded(1, 3), None);
+        assert_eq!(list.push_back_bounded(2, 3), None);
+        assert_eq!(list.push_back_bounded(3, 3), None);
+        assert_eq!(list.push_back_bounded(4, 3), Some(Rc::new(1)));
+
+        assert_eq!(values(&list), vec![2, 3, 4]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_split_by_a_predicate() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        let odd = list.split_by(|i| i % 2 == 0);
+
+        assert_eq!(values(&list), vec![2, 4]);
+        assert_eq!(values(&odd), vec![1, 3]);
+        assert!(list.links_consistent());
+        assert!(odd.links_consistent());
+    }
+
+    #[test]
+    fn it_can_get_each_consecutive_group() {
+        let list = doubly_linked_list!(1, 2, 3);
+
+        let groups: Vec<Vec<i32>> = list.each_cons(2).iter().map(values).collect();
+
+        assert_eq!(groups, vec![vec![1, 2], vec![2, 3]]);
+        assert!(list.each_cons(4).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_on_empty_consecutive_groups() {
+        doubly_linked_list!(1, 2, 3).each_cons(0);
+    }
+
+    #[test]
+    fn it_can_extend_the_front_in_reverse() {
+        let list = doubly_linked_list!(3);
+
+        list.extend_front_reversed(vec![1, 2]);
+
+        assert_eq!(values(&list), vec![2, 1, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_count_values_in_order_of_appearance() {
+        let list = doubly_linked_list!(2, 1, 2, 3, 1);
+
+        assert_eq!(
+            list.value_counts(),
+            vec![(Rc::new(2), 2), (Rc::new(1), 2), (Rc::new(3), 1)]
+        );
+    }
+
+    #[test]
+    fn it_can_be_consumed_into_chunks() {
+        let tracker = Rc::new(());
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+        let tracked = doubly_linked_list!(Rc::clone(&tracker), Rc::clone(&tracker));
+
+        assert_eq!(list.into_chunks(2), vec![vec![1, 2], vec![3, 4], vec![5]]);
+        drop(tracked.into_chunks(2));
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_on_empty_chunks() {
+        doubly_linked_list!(1, 2).into_chunks(0);
+    }
+
+    #[test]
+    fn it_can_defer_matching_values() {
+        let list = doubly_linked_list!(1, 2, 3, 4);
+
+        list.defer(|i| i % 2 == 0);
+
+        assert_eq!(values(&list), vec![1, 3, 2, 4]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_report_value_strong_counts() {
+        let list = doubly_linked_list!(1, 2, 3);
+        let _shared = list.share(1).unwrap();
+
+        assert_eq!(list.value_strong_counts(), vec![1, 2, 1]);
+    }
+
+    #[test]
+    fn it_appends_a_nonempty_list() {
+        let list = doubly_linked_list!(1);
+
+        assert!(list.append_if_nonempty(doubly_linked_list!(2, 3)));
+
+        assert_eq!(values(&list), vec![1, 2, 3]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_skips_appending_an_empty_list() {
+        let list = doubly_linked_list!(1);
+
+        assert!(!list.append_if_nonempty(DoublyLinkedList::new()));
+
+        assert_eq!(values(&list), vec![1]);
+    }
+
+    #[test]
+    fn it_can_reverse_complete_chunks() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        assert_eq!(list.reverse_chunks(2), 2);
+
+        assert_eq!(values(&list), vec![2, 1, 4, 3, 5]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_map_indices_to_values() {
+        let list = doubly_linked_list!("a", "b", "c");
+
+        assert_eq!(
+            list.to_index_map(),
+            HashMap::from([(0, Rc::new("a")), (1, Rc::new("b")), (2, Rc::new("c"))])
+        );
+    }
+
+    #[test]
+    fn it_can_merge_sorted_lists_without_duplicates() {
+        let list = doubly_linked_list!(1, 3, 5);
+
+        list.merge_dedup(doubly_linked_list!(2, 3, 6));
+
+        assert_eq!(values(&list), vec![1, 2, 3, 5, 6]);
+        assert!(list.links_consistent());
+    }
+
+    #[test]
+    fn it_can_find_a_value_with_its_index() {
+        let list = doubly_linked_list!(3, 1, -4, 1, -5);
+
+        let (index, handle) = list.find_with_index(|i| *i < 0).unwrap();
+        assert_eq!(index, 2);
+        list.remove_node(&handle);
+
+        assert_eq!(values(&list), vec![3, 1, 1, -5]);
+        assert!(list.links_consistent());
+        assert!(list.find_with_index(|i| *i > 5).is_none());
+    }
+
+    #[test]
+    fn it_can_clone_a_range() {
+        let list = doubly_linked_list!(10, 20, 30, 40);
+
+        let range = list.clone_range(1..3);
+
+        assert_eq!(values(&range), vec![20, 30]);
+        assert_eq!(values(&list), vec![10, 20, 30, 40]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_cloning_an_out_of_bounds_range() {
+        doubly_linked_list!(10, 20).clone_range(1..3);
+    }
+
+    #[test]
+    fn it_can_swap_ranges() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+
+        list.swap_ranges(0..2, 3..5);
+
+        assert_eq!(list.assert_consistent_with(&[4, 5, 3, 1, 2]), Ok(()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_swapping_overlapping_ranges() {
+        doubly_linked_list!(1, 2, 3, 4).swap_ranges(0..2, 1..3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_swapping_ranges_of_different_lengths() {
+        doubly_linked_list!(1, 2, 3, 4).swap_ranges(0..1, 2..4);
+    }
+
+    #[test]
+    fn it_can_find_the_longest_increasing_run() {
+        assert_eq!(
+            doubly_linked_list!(1, 2, 1, 2, 3, 4).longest_increasing_run(),
+            4
+        );
+        assert_eq!(doubly_linked_list!(2, 2, 2).longest_increasing_run(), 1);
+        assert_eq!(doubly_linked_list!(1).longest_increasing_run(), 1);
+        assert_eq!(DoublyLinkedList::<i32>::new().longest_increasing_run(), 0);
+    }
+
+    #[test]
+    fn it_can_reduce_each_window() {
+        let list = doubly_linked_list!(1, 3, 2, 5);
+
+        let maxima = list.window_reduce(2, |window| window.iter().map(|i| **i).max().unwrap());
+
+        assert_eq!(values(&maxima), vec![3, 3, 5]);
+        assert!(list.window_reduce(5, |_| 0).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_on_empty_windows() {
+        doubly_linked_list!(1, 2).window_reduce(0, |_| 0);
+    }
+
+    #[test]
+    fn it_can_render_the_links_as_arrows() {
+        assert_eq!(DoublyLinkedList::<i32>::new().to_arrow_string(), "(empty)");
+        assert_eq!(doubly_linked_list!(1).to_arrow_string(), "1");
+        assert_eq!(
+            doubly_linked_list!(1, 2, 3).to_arrow_string(),
+            "1 <-> 2 <-> 3"
+        );
+    }
+
+    #[test]
+    fn it_can_detach_a_range() {
+        let list = doubly_linked_list!(1, 2, 3, 4, 5);
+        let other = doubly_linked_list!(9);
+        let middle = handles(&list)[2].clone();
+
+        let detached = list.detach_range(1..4);
+        other.append(detached);
+
+        assert_eq!(values(&list), vec![1, 5]);
+        assert_eq!(values(&other), vec![9, 2, 3, 4]);
+        assert!(handles(&other)[2].same_node(&middle));
+        assert!(list.validate().is_ok());
+        assert!(other.validate().is_ok());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_when_detaching_an_out_of_bounds_range() {
+        doubly_linked_list!(1, 2).detach_range(1..3);
+    }
+
+    #[test]
+    fn it_can_estimate_heap_bytes() {
+        let empty = DoublyLinkedList::<u64>::new();
+        let one = doubly_linked_list!(1u64);
+        let three = doubly_linked_list!(1u64, 2, 3);
+
+        assert_eq!(empty.estimated_heap_bytes(), 0);
+        assert!(one.estimated_heap_bytes() > std::mem::size_of::<u64>());
+        assert_eq!(three.estimated_heap_bytes(), 3 * one.estimated_heap_bytes());
     }
 }
@@ -0,0 +1,98 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{DoublyLinkedList, NodeHandle};
+
+// A least recently used cache. Entries are kept in a list ordered from most
+// to least recently used, with a map from each key to its node so lookups
+// can promote an entry without searching the list.
+pub struct LruCache<K, V> {
+    capacity: usize,
+    entries: DoublyLinkedList<'static, (K, V)>,
+    nodes: HashMap<K, NodeHandle<'static, (K, V)>>,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity,
+            entries: DoublyLinkedList::new(),
+            nodes: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // Look up `key`, marking it as the most recently used entry.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let handle = self.nodes.get(key)?;
+        self.entries.move_to_front(handle);
+        Some(handle.value().1.clone())
+    }
+
+    // Insert or update `key` as the most recently used entry, evicting the
+    // least recently used one if the cache is over capacity.
+    pub fn put(&mut self, key: K, value: V) {
+        if let Some(handle) = self.nodes.remove(&key) {
+            self.entries.remove_node(&handle);
+        }
+        self.entries.push_front((key.clone(), value));
+        let handle = self.entries.front_handle().expect("entry was just pushed");
+        self.nodes.insert(key, handle);
+        if self.nodes.len() > self.capacity {
+            if let Some(evicted) = self.entries.pop_back() {
+                self.nodes.remove(&evicted.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_misses_unknown_keys() {
+        let mut cache = LruCache::<&str, i32>::new(2);
+
+        assert_eq!(cache.get(&"a"), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn it_promotes_entries_on_a_hit() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+
+        assert_eq!(cache.get(&"a"), Some(1));
+        cache.put("c", 3);
+
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"a"), Some(1));
+        assert_eq!(cache.get(&"c"), Some(3));
+    }
+
+    #[test]
+    fn it_evicts_the_least_recently_used_entry() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("a", 10);
+        cache.put("c", 3);
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"a"), Some(10));
+        assert_eq!(cache.get(&"c"), Some(3));
+    }
+}